@@ -0,0 +1,487 @@
+//! The workflow file format.
+//!
+//! Values are validated while deserializing, so a bad duration, accelerator or cron
+//! expression is reported at its line and column like any syntax error.
+
+use automat_core::{
+  Accelerator, Axis, Button, ClipboardChange, ClipboardEvent, ClipboardSelection,
+  FileSystemTrigger, FsEventKind, ProcessBackend, ProcessEvent, ProcessTrigger, ScheduleTrigger,
+  WatchBackend, Window,
+};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The contents of a workflow file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowFile {
+  #[serde(deserialize_with = "non_empty")]
+  pub workflows: Vec<Workflow>,
+}
+
+impl WorkflowFile {
+  /// Makes relative paths relative to `base`, the directory of the workflow file, and
+  /// expands a leading `~` to the home directory.
+  pub fn resolve_paths(&mut self, base: &Path) {
+    for workflow in &mut self.workflows {
+      if let TriggerSpec::Fs(fs) = &mut workflow.trigger {
+        for path in &mut fs.paths {
+          *path = resolve_path(base, path);
+        }
+      }
+
+      for action in &mut workflow.actions {
+        if let ActionSpec::Run(RunSpec { cwd: Some(cwd), .. }) = action {
+          *cwd = resolve_path(base, cwd);
+        }
+      }
+    }
+  }
+}
+
+/// A trigger and the actions run, in order, every time it fires.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workflow {
+  /// Shown in error messages.
+  pub name: String,
+  pub trigger: TriggerSpec,
+  #[serde(deserialize_with = "non_empty")]
+  pub actions: Vec<ActionSpec>,
+}
+
+/// What starts a workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TriggerSpec {
+  /// Every interval, such as `30s` or `1h 30m`.
+  Interval(#[serde(deserialize_with = "positive_duration")] Duration),
+  /// On a cron schedule in local time, such as `0 9 * * Mon-Fri`.
+  Schedule(#[serde(deserialize_with = "cron_expression")] String),
+  /// When a global key combination such as `Super+Shift+N` is pressed.
+  Hotkey(#[serde(deserialize_with = "accelerator")] Accelerator),
+  /// When files change.
+  Fs(FsSpec),
+  /// When the clipboard content changes.
+  Clipboard(ClipboardSpec),
+  /// When a process starts or exits.
+  Process(ProcessSpec),
+  /// When the focused window changes.
+  WindowFocus(WindowFocusSpec),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FsSpec {
+  #[serde(deserialize_with = "non_empty")]
+  pub paths: Vec<PathBuf>,
+  #[serde(default = "default_recursive")]
+  pub recursive: bool,
+  /// Globs such as `*.rs`, or `!target/**` to skip matches.
+  #[serde(default, deserialize_with = "globs")]
+  pub globs: Vec<String>,
+  /// Only fire for these kinds of changes. Any change when unset.
+  pub events: Option<Vec<FsEventSpec>>,
+  /// Wait until changed paths have been quiet this long, firing once per path.
+  #[serde(default, with = "humantime_serde")]
+  pub debounce: Option<Duration>,
+  /// Skip paths ignored by git.
+  #[serde(default)]
+  pub gitignore: bool,
+  #[serde(default)]
+  pub backend: FsBackendSpec,
+  /// How often paths are rescanned when polled.
+  #[serde(
+    default = "default_poll_interval",
+    deserialize_with = "positive_duration"
+  )]
+  pub poll_interval: Duration,
+}
+
+impl FsSpec {
+  pub const fn backend(&self) -> WatchBackend {
+    match self.backend {
+      FsBackendSpec::Native => WatchBackend::Native,
+      FsBackendSpec::Poll => WatchBackend::Poll {
+        interval: self.poll_interval,
+      },
+      FsBackendSpec::Auto => WatchBackend::Auto {
+        poll_interval: self.poll_interval,
+      },
+    }
+  }
+}
+
+const fn default_recursive() -> bool {
+  true
+}
+
+const fn default_poll_interval() -> Duration {
+  Duration::from_secs(2)
+}
+
+/// How paths are watched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsBackendSpec {
+  /// The platform's file system notifications.
+  #[default]
+  Native,
+  /// Rescan the paths every `poll_interval`, needed for network and FUSE mounts.
+  Poll,
+  /// Native notifications, polling where they do not work.
+  Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsEventSpec {
+  Create,
+  Modify,
+  Remove,
+  Rename,
+}
+
+impl From<FsEventSpec> for FsEventKind {
+  fn from(kind: FsEventSpec) -> Self {
+    match kind {
+      FsEventSpec::Create => Self::Create,
+      FsEventSpec::Modify => Self::Modify,
+      FsEventSpec::Remove => Self::Remove,
+      FsEventSpec::Rename => Self::Rename,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClipboardSpec {
+  /// Only fire when the new content has plain text containing this.
+  pub contains: Option<String>,
+  /// Only fire for this kind of content.
+  pub content: Option<ClipboardContent>,
+  /// The selection to watch.
+  pub selection: SelectionSpec,
+}
+
+impl ClipboardSpec {
+  pub const fn selection(&self) -> ClipboardSelection {
+    match self.selection {
+      SelectionSpec::Clipboard => ClipboardSelection::Clipboard,
+      SelectionSpec::Primary => ClipboardSelection::Primary,
+    }
+  }
+
+  pub fn matches(&self, change: &ClipboardChange) -> bool {
+    let event = &change.content;
+    let content = match event {
+      ClipboardEvent::Text(_) => ClipboardContent::Text,
+      ClipboardEvent::Html { .. } => ClipboardContent::Html,
+      ClipboardEvent::Image { .. } => ClipboardContent::Image,
+      ClipboardEvent::Files(_) => ClipboardContent::Files,
+    };
+
+    self.content.is_none_or(|expected| expected == content)
+      && self.contains.as_ref().is_none_or(|needle| {
+        event
+          .text()
+          .is_some_and(|text| text.contains(needle.as_str()))
+      })
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionSpec {
+  /// Explicit copies.
+  #[default]
+  Clipboard,
+  /// Text selected with the mouse, on X11 and Wayland.
+  Primary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardContent {
+  Text,
+  Html,
+  Image,
+  Files,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProcessSpec {
+  /// Process name glob such as `python*`, compared case-insensitively. Any process
+  /// when unset.
+  #[serde(deserialize_with = "process_glob")]
+  pub name: Option<String>,
+  /// Executable path glob such as `**/cargo`.
+  #[serde(deserialize_with = "process_glob")]
+  pub exe: Option<String>,
+  /// Only processes running as this user.
+  pub user: Option<String>,
+  pub event: ProcessEventKind,
+  pub backend: ProcessBackendSpec,
+}
+
+impl ProcessSpec {
+  pub const fn backend(&self) -> ProcessBackend {
+    match self.backend {
+      ProcessBackendSpec::Poll => ProcessBackend::Poll,
+      ProcessBackendSpec::Events => ProcessBackend::Events,
+    }
+  }
+
+  pub fn matches(&self, event: &ProcessEvent) -> bool {
+    let kind = match event {
+      ProcessEvent::Started(_) => ProcessEventKind::Started,
+      ProcessEvent::Exited(_) => ProcessEventKind::Exited,
+    };
+
+    self.event == ProcessEventKind::Any || self.event == kind
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessBackendSpec {
+  /// Compare the process list twice a second.
+  #[default]
+  Poll,
+  /// Kernel notifications, catching short-lived processes. Needs root on Linux and
+  /// falls back to polling.
+  Events,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessEventKind {
+  #[default]
+  Started,
+  Exited,
+  Any,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowFocusSpec {
+  /// Only fire when the title contains this, ignoring case.
+  pub title: Option<String>,
+  /// Only fire when the executable path contains this, ignoring case.
+  pub executable: Option<String>,
+}
+
+impl WindowFocusSpec {
+  pub fn matches(&self, window: Window) -> bool {
+    fn contains(haystack: Option<String>, needle: &str) -> bool {
+      haystack.is_some_and(|haystack| haystack.to_lowercase().contains(&needle.to_lowercase()))
+    }
+
+    self
+      .title
+      .as_ref()
+      .is_none_or(|title| contains(window.title(), title))
+      && self
+        .executable
+        .as_ref()
+        .is_none_or(|executable| contains(window.executable_path(), executable))
+  }
+}
+
+/// A single step of a workflow.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ActionSpec {
+  /// Types text.
+  Text(String),
+  /// Presses a key or key combination, such as `Enter` or `Ctrl+Shift+T`.
+  Key(#[serde(deserialize_with = "accelerator")] Accelerator),
+  MoveMouse(MoveMouseSpec),
+  Click(MouseButton),
+  Scroll(ScrollSpec),
+  /// Acts on the window that triggered the workflow, or the focused window.
+  Window(WindowOperation),
+  MoveWindow(MoveWindowSpec),
+  ResizeWindow(ResizeWindowSpec),
+  Open(OpenSpec),
+  Run(RunSpec),
+  /// Waits before the next step.
+  Delay(#[serde(with = "humantime_serde")] Duration),
+}
+
+impl ActionSpec {
+  /// The key the action is written with, used in error messages.
+  pub const fn label(&self) -> &'static str {
+    match self {
+      Self::Text(_) => "text",
+      Self::Key(_) => "key",
+      Self::MoveMouse(_) => "move_mouse",
+      Self::Click(_) => "click",
+      Self::Scroll(_) => "scroll",
+      Self::Window(_) => "window",
+      Self::MoveWindow(_) => "move_window",
+      Self::ResizeWindow(_) => "resize_window",
+      Self::Open(_) => "open",
+      Self::Run(_) => "run",
+      Self::Delay(_) => "delay",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MoveMouseSpec {
+  pub x: i32,
+  pub y: i32,
+  /// Move by `x` and `y` instead of to them.
+  #[serde(default)]
+  pub relative: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseButton {
+  Left,
+  Right,
+  Middle,
+}
+
+impl From<MouseButton> for Button {
+  fn from(button: MouseButton) -> Self {
+    match button {
+      MouseButton::Left => Self::Left,
+      MouseButton::Right => Self::Right,
+      MouseButton::Middle => Self::Middle,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScrollSpec {
+  pub length: i32,
+  #[serde(default)]
+  pub axis: ScrollAxis,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollAxis {
+  Horizontal,
+  #[default]
+  Vertical,
+}
+
+impl From<ScrollAxis> for Axis {
+  fn from(axis: ScrollAxis) -> Self {
+    match axis {
+      ScrollAxis::Horizontal => Self::Horizontal,
+      ScrollAxis::Vertical => Self::Vertical,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowOperation {
+  Close,
+  Focus,
+  Maximize,
+  Minimize,
+  Raise,
+  Restore,
+  Unmaximize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MoveWindowSpec {
+  pub x: i32,
+  pub y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResizeWindowSpec {
+  pub width: u32,
+  pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenSpec {
+  pub url: String,
+  /// Browser to open the URL with instead of the default one.
+  pub browser: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunSpec {
+  /// Program to run, looked up in `PATH`. Not passed through a shell.
+  pub command: String,
+  #[serde(default)]
+  pub args: Vec<String>,
+  pub cwd: Option<PathBuf>,
+  /// Start the program and continue without waiting for it to exit.
+  #[serde(default)]
+  pub detach: bool,
+}
+
+fn resolve_path(base: &Path, path: &Path) -> PathBuf {
+  if let Ok(rest) = path.strip_prefix("~")
+    && let Some(home) = std::env::home_dir()
+  {
+    return home.join(rest);
+  }
+
+  base.join(path)
+}
+
+fn non_empty<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Deserialize<'de>,
+{
+  let values = Vec::deserialize(deserializer)?;
+  if values.is_empty() {
+    return Err(D::Error::custom("expected at least one entry"));
+  }
+  Ok(values)
+}
+
+fn positive_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+  let duration = humantime_serde::deserialize(deserializer)?;
+  if duration == Duration::ZERO {
+    return Err(D::Error::custom("interval must be longer than zero"));
+  }
+  Ok(duration)
+}
+
+fn cron_expression<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+  let expression = String::deserialize(deserializer)?;
+  ScheduleTrigger::validate_expression(&expression).map_err(D::Error::custom)?;
+  Ok(expression)
+}
+
+fn globs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+  let globs = Vec::<String>::deserialize(deserializer)?;
+  for glob in &globs {
+    FileSystemTrigger::validate_glob(glob).map_err(D::Error::custom)?;
+  }
+  Ok(globs)
+}
+
+fn process_glob<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+  let glob = String::deserialize(deserializer)?;
+  ProcessTrigger::validate_glob(&glob).map_err(D::Error::custom)?;
+  Ok(Some(glob))
+}
+
+fn accelerator<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Accelerator, D::Error> {
+  let accelerator = String::deserialize(deserializer)?;
+  Accelerator::parse(&accelerator).map_err(D::Error::custom)
+}
@@ -0,0 +1,203 @@
+//! Running workflows and reloading them when their file changes.
+
+use crate::config::{Workflow, WorkflowFile};
+use crate::error::{Error, ReloadError, ReloadStopped};
+use crate::{load, report, run};
+use automat_core::{
+  Automat, AutomatHandle, FileSystemBuilder, FileSystemTrigger, RestartPolicy, Supervision,
+  TriggerId, TriggerState, await_shutdown,
+};
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+/// How long to wait after the workflow file changed before reloading it.
+///
+/// Editors often save in several steps, such as truncating and writing the file or
+/// writing a temporary file and renaming it, each producing its own event.
+const RELOAD_DELAY: Duration = Duration::from_millis(200);
+
+/// Runs the workflows in `path` until Ctrl-C is pressed.
+///
+/// With `reload`, changes to the file are applied while running: removed workflows are
+/// stopped, new ones started and unchanged ones keep running with their state.
+pub async fn run(path: &Path, mut reload: bool) -> Result<(), Error> {
+  let file = load::load(path)?;
+
+  // A broken workflow is reported and stays stopped until the next reload, instead of
+  // shutting down every other workflow.
+  let handle = Automat::new()
+    .on_error(|err| match err {
+      automat_core::Error::CallbackError(err) => report(err),
+      err => report(err),
+    })
+    .with_supervision(Supervision::new(RestartPolicy::Never).escalate(false))
+    .spawn();
+
+  let mut running = Running::new(handle.clone());
+  running.apply(file).await;
+
+  let (tx, mut rx) = mpsc::channel(1);
+  if reload {
+    // The workflows keep running if the watcher gives up; only reloading stops.
+    let watcher = watch(path, tx)?;
+    handle.add_supervised(
+      Box::new(watcher),
+      Supervision::new(RestartPolicy::OnFailure).escalate(false),
+    );
+  }
+
+  let result = loop {
+    tokio::select! {
+      result = await_shutdown() => break result,
+      () = handle.wait() => break Ok(()),
+      changed = rx.recv(), if reload => {
+        if changed.is_none() {
+          // The watcher, and the sender it held, are dropped once it is not restarted.
+          reload = false;
+          handle.report_error(ReloadStopped { path: path.to_path_buf() }.into());
+          continue;
+        }
+        sleep(RELOAD_DELAY).await;
+        while rx.try_recv().is_ok() {}
+        running.reload(path).await;
+      }
+    }
+  };
+
+  handle.shutdown().await;
+  result.map_err(Error::from)
+}
+
+/// Watches the workflow file and signals `tx` when it changes.
+///
+/// The directory is watched rather than the file, since editors often replace the file
+/// instead of writing to it, which would end a watch on the file itself.
+fn watch(path: &Path, tx: mpsc::Sender<()>) -> Result<FileSystemTrigger, Error> {
+  let path = path.canonicalize().map_err(|source| Error::Read {
+    path: path.to_path_buf(),
+    source,
+  })?;
+  let name = path.file_name().map(ToOwned::to_owned);
+  let dir = path.parent().unwrap_or(&path);
+
+  Ok(
+    FileSystemBuilder::new()
+      .watch_non_recursive(dir)
+      .on_event_blocking(move |ctx| {
+        let changed = ctx
+          .data
+          .paths()
+          .any(|changed| changed.file_name() == name.as_deref());
+
+        if changed {
+          // A full channel means a reload is already pending.
+          let _ = tx.try_send(());
+        }
+        Ok(())
+      }),
+  )
+}
+
+/// The running workflows and the triggers started for them.
+struct Running {
+  handle: AutomatHandle,
+  workflows: Vec<(Workflow, TriggerId)>,
+}
+
+impl Running {
+  const fn new(handle: AutomatHandle) -> Self {
+    Self {
+      handle,
+      workflows: Vec::new(),
+    }
+  }
+
+  /// Loads the file again and applies it, keeping the current workflows if it is invalid.
+  async fn reload(&mut self, path: &Path) {
+    match load::load(path) {
+      Ok(file) => {
+        let changes = self.apply(file).await;
+        if !changes.is_empty() {
+          println!("reloaded {}: {changes}", path.display());
+        }
+      }
+      Err(source) => {
+        let err = ReloadError {
+          path: path.to_path_buf(),
+          source,
+        };
+        self.handle.report_error(err.into());
+      }
+    }
+  }
+
+  /// Starts and stops triggers so they match `file`.
+  ///
+  /// Workflows that did not change keep their trigger, unless it has stopped, in which
+  /// case it is started again.
+  async fn apply(&mut self, file: WorkflowFile) -> Changes {
+    let mut previous = std::mem::take(&mut self.workflows);
+    let mut added = Vec::new();
+    let mut changes = Changes::default();
+
+    for workflow in file.workflows {
+      let unchanged = previous
+        .iter()
+        .position(|(running, id)| *running == workflow && self.is_alive(*id));
+
+      if let Some(index) = unchanged {
+        self.workflows.push(previous.swap_remove(index));
+        changes.unchanged += 1;
+      } else {
+        added.push(workflow);
+      }
+    }
+
+    // Stop the old triggers first, so a hotkey whose actions changed is released before
+    // it is grabbed again.
+    for (_, id) in previous {
+      let _ = self.handle.remove(id).await;
+      changes.removed += 1;
+    }
+
+    for workflow in added {
+      let id = self.handle.add(run::trigger(Arc::new(workflow.clone())));
+      self.workflows.push((workflow, id));
+      changes.added += 1;
+    }
+
+    changes
+  }
+
+  fn is_alive(&self, id: TriggerId) -> bool {
+    !matches!(self.handle.state(id), None | Some(TriggerState::Stopped))
+  }
+}
+
+/// What a reload changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Changes {
+  added: usize,
+  removed: usize,
+  unchanged: usize,
+}
+
+impl Changes {
+  const fn is_empty(&self) -> bool {
+    self.added == 0 && self.removed == 0
+  }
+}
+
+impl Display for Changes {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} started, {} stopped, {} unchanged",
+      self.added, self.removed, self.unchanged
+    )
+  }
+}
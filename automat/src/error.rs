@@ -0,0 +1,104 @@
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+  #[error("cannot read {}: {source}", path.display())]
+  Read { path: PathBuf, source: io::Error },
+
+  #[error("{}: unsupported workflow file, expected a .toml, .yaml or .yml extension", .0.display())]
+  UnsupportedFormat(PathBuf),
+
+  #[error("{0}")]
+  Invalid(Diagnostic),
+
+  #[error(transparent)]
+  Automat(#[from] automat_core::Error),
+}
+
+/// An error in a workflow file, pointing at the offending line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+  pub path: PathBuf,
+  /// 1-based line.
+  pub line: usize,
+  /// 1-based column, in characters.
+  pub column: usize,
+  /// Number of characters to underline.
+  pub width: usize,
+  pub message: String,
+  /// The offending line, shown under the message.
+  pub source_line: Option<String>,
+}
+
+impl Display for Diagnostic {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}:{}:{}: {}",
+      self.path.display(),
+      self.line,
+      self.column,
+      self.message
+    )?;
+
+    if let Some(source_line) = &self.source_line {
+      let gutter = " ".repeat(self.line.to_string().len());
+      let indent = " ".repeat(self.column.saturating_sub(1));
+      let underline = "^".repeat(self.width.max(1));
+      write!(
+        f,
+        "\n{gutter} |\n{} | {source_line}\n{gutter} | {indent}{underline}",
+        self.line
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+/// A workflow step that failed, reported through the runner's error handler.
+#[derive(Debug, Error)]
+#[error("workflow `{workflow}` failed at step {step} ({action}): {source}")]
+pub struct StepError {
+  pub workflow: String,
+  /// 1-based index of the step.
+  pub step: usize,
+  pub action: &'static str,
+  pub source: automat_core::Error,
+}
+
+impl From<StepError> for automat_core::Error {
+  fn from(err: StepError) -> Self {
+    Self::CallbackError(Box::new(err))
+  }
+}
+
+/// A change to the workflow file that could not be applied.
+#[derive(Debug, Error)]
+#[error("cannot reload {}, keeping the previous workflows: {source}", path.display())]
+pub struct ReloadError {
+  pub path: PathBuf,
+  pub source: Error,
+}
+
+impl From<ReloadError> for automat_core::Error {
+  fn from(err: ReloadError) -> Self {
+    Self::CallbackError(Box::new(err))
+  }
+}
+
+/// The workflow file stopped being watched, so changes to it are no longer applied.
+#[derive(Debug, Error)]
+#[error("stopped watching {} for changes; the workflows keep running without hot reload", path.display())]
+pub struct ReloadStopped {
+  pub path: PathBuf,
+}
+
+impl From<ReloadStopped> for automat_core::Error {
+  fn from(err: ReloadStopped) -> Self {
+    Self::CallbackError(Box::new(err))
+  }
+}
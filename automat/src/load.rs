@@ -0,0 +1,85 @@
+//! Reading workflow files.
+
+use crate::config::WorkflowFile;
+use crate::error::{Diagnostic, Error};
+use std::path::{Path, PathBuf};
+
+/// Syntax of a workflow file, chosen by its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  Toml,
+  Yaml,
+}
+
+impl Format {
+  pub fn from_path(path: &Path) -> Option<Self> {
+    match path.extension()?.to_str()? {
+      "toml" => Some(Self::Toml),
+      "yaml" | "yml" => Some(Self::Yaml),
+      _ => None,
+    }
+  }
+}
+
+/// Reads and validates a workflow file.
+///
+/// Relative paths in the file are resolved against the file's directory.
+pub fn load(path: &Path) -> Result<WorkflowFile, Error> {
+  let format =
+    Format::from_path(path).ok_or_else(|| Error::UnsupportedFormat(path.to_path_buf()))?;
+  let source = std::fs::read_to_string(path).map_err(|source| Error::Read {
+    path: path.to_path_buf(),
+    source,
+  })?;
+
+  let mut file = parse(&source, format, path).map_err(Error::Invalid)?;
+  file.resolve_paths(path.parent().unwrap_or_else(|| Path::new(".")));
+  Ok(file)
+}
+
+/// Parses a workflow file. `path` is only used in diagnostics.
+pub fn parse(source: &str, format: Format, path: &Path) -> Result<WorkflowFile, Diagnostic> {
+  match format {
+    Format::Toml => toml::from_str(source).map_err(|err| {
+      let span = err.span().unwrap_or(0..0);
+      diagnostic(source, path, span.start, span.len(), err.message().trim())
+    }),
+    Format::Yaml => {
+      // Enums are written as single-key maps (`interval: 5m`) rather than YAML tags.
+      let deserializer = serde_yaml::Deserializer::from_str(source);
+      serde_yaml::with::singleton_map_recursive::deserialize(deserializer).map_err(|err| {
+        let message = err.to_string();
+        let Some(location) = err.location() else {
+          return diagnostic(source, path, 0, 0, &message);
+        };
+
+        // The message ends with the location, which the diagnostic shows separately.
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        let message = message.strip_suffix(&suffix).unwrap_or(&message);
+        diagnostic(source, path, location.index(), 1, message)
+      })
+    }
+  }
+}
+
+/// Builds a diagnostic for the error at byte `offset`, underlining `len` bytes.
+fn diagnostic(source: &str, path: &Path, offset: usize, len: usize, message: &str) -> Diagnostic {
+  let offset = offset.min(source.len());
+  let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+  let line_end = source[offset..]
+    .find('\n')
+    .map_or(source.len(), |i| offset + i);
+  let source_line = &source[line_start..line_end];
+
+  // Only underline up to the end of the line for spans covering several lines.
+  let underlined = &source[offset..(offset + len).min(line_end)];
+
+  Diagnostic {
+    path: PathBuf::from(path),
+    line: source[..offset].matches('\n').count() + 1,
+    column: source[line_start..offset].chars().count() + 1,
+    width: underlined.chars().count(),
+    message: message.to_owned(),
+    source_line: (!source_line.trim().is_empty()).then(|| source_line.trim_end().to_owned()),
+  }
+}
@@ -0,0 +1,97 @@
+//! # automat
+//!
+//! Runs automations described in TOML or YAML workflow files, so they can be changed
+//! without recompiling.
+//!
+//! A workflow maps one trigger to a list of actions run in order every time it fires:
+//!
+//! ```toml
+//! [[workflows]]
+//! name = "Summon terminal"
+//! trigger = { hotkey = "Super+Return" }
+//! actions = [{ run = { command = "alacritty", detach = true } }]
+//!
+//! [[workflows]]
+//! name = "Tidy downloads"
+//! trigger = { fs = { paths = ["~/Downloads"], recursive = false } }
+//! actions = [
+//!   { delay = "2s" },
+//!   { run = { command = "./tidy.sh", cwd = "~/scripts" } },
+//! ]
+//! ```
+//!
+//! See `examples/` for every trigger and action.
+//!
+//! ```text
+//! automat run workflows.toml     # run until Ctrl-C, applying changes to the file
+//! automat check workflows.yaml   # only validate the file
+//! ```
+
+mod config;
+mod daemon;
+mod error;
+mod load;
+mod run;
+
+use crate::error::Error;
+use clap::{Parser, Subcommand};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Debug, Parser)]
+#[command(
+  version,
+  about = "Runs automations described in TOML or YAML workflow files"
+)]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+  /// Runs the workflows in a file until Ctrl-C is pressed.
+  ///
+  /// Changes to the file are applied without restarting.
+  Run {
+    file: PathBuf,
+    /// Ignore changes to the file after it was loaded.
+    #[arg(long)]
+    no_reload: bool,
+  },
+  /// Validates a workflow file without running it.
+  Check { file: PathBuf },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+  match execute(Cli::parse()).await {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      report(err);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+async fn execute(cli: Cli) -> Result<(), Error> {
+  match cli.command {
+    Command::Run { file, no_reload } => daemon::run(&file, !no_reload).await?,
+    Command::Check { file } => {
+      let workflows = load::load(&file)?;
+      println!(
+        "{}: {} workflow(s) OK",
+        file.display(),
+        workflows.workflows.len()
+      );
+    }
+  }
+
+  Ok(())
+}
+
+#[expect(clippy::print_stderr, reason = "errors are reported on the terminal")]
+fn report(err: impl Display) {
+  eprintln!("error: {err}");
+}
@@ -0,0 +1,170 @@
+//! Turning workflows into triggers and running their actions.
+
+use crate::config::{ActionSpec, FsSpec, ProcessSpec, TriggerSpec, WindowOperation, Workflow};
+use crate::error::StepError;
+use automat_core::{
+  Action as _, ActionAsync as _, ClipboardTrigger, CloseWindow, Error, FileSystemBuilder,
+  FocusWindow, HotkeyTrigger, IntervalTrigger, KeyboardAction, MaximizeWindow, MinimizeWindow,
+  MouseAction, MoveWindow, OpenInBrowser, ProcessTrigger, RaiseWindow, ResizeWindow, RestoreWindow,
+  Result, RunCommand, ScheduleTrigger, Trigger, UnmaximizeWindow, Window, WindowIdentifier,
+  WindowTrigger,
+};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Builds the trigger that runs the workflow's actions.
+pub fn trigger(workflow: Arc<Workflow>) -> Box<dyn Trigger> {
+  match workflow.trigger.clone() {
+    TriggerSpec::Interval(interval) => Box::new(IntervalTrigger::new(interval, move |_| {
+      run_if(&workflow, true, None)
+    })),
+    TriggerSpec::Schedule(expression) => Box::new(ScheduleTrigger::new(&expression, move |_| {
+      run_if(&workflow, true, None)
+    })),
+    TriggerSpec::Hotkey(accelerator) => {
+      Box::new(HotkeyTrigger::from_accelerator(accelerator, move |_| {
+        run_if(&workflow, true, None)
+      }))
+    }
+    TriggerSpec::Fs(fs) => {
+      Box::new(fs_builder(&fs).on_event(move |_| run_if(&workflow, true, None)))
+    }
+    TriggerSpec::Clipboard(spec) => {
+      let selection = spec.selection();
+      Box::new(
+        ClipboardTrigger::new(move |ctx| run_if(&workflow, spec.matches(&ctx.data), None))
+          .with_selections([selection]),
+      )
+    }
+    TriggerSpec::Process(spec) => Box::new(process_trigger(&spec, workflow)),
+    TriggerSpec::WindowFocus(spec) => Box::new(WindowTrigger::new(move |ctx| {
+      run_if(&workflow, spec.matches(ctx.data), Some(ctx.data))
+    })),
+  }
+}
+
+fn fs_builder(fs: &FsSpec) -> FileSystemBuilder {
+  let mut builder = FileSystemBuilder::new()
+    .watch_many(fs.paths.iter().map(|path| (path, fs.recursive)))
+    .backend(fs.backend())
+    .gitignore(fs.gitignore);
+
+  for glob in &fs.globs {
+    builder = builder.glob(glob);
+  }
+  if let Some(events) = &fs.events {
+    builder = builder.event_kinds(events.iter().copied().map(Into::into));
+  }
+  if let Some(delay) = fs.debounce {
+    builder = builder.debounce(delay);
+  }
+
+  builder
+}
+
+fn process_trigger(spec: &ProcessSpec, workflow: Arc<Workflow>) -> ProcessTrigger {
+  let mut trigger = ProcessTrigger::new({
+    let spec = spec.clone();
+    move |ctx| run_if(&workflow, spec.matches(&ctx.data), None)
+  })
+  .with_backend(spec.backend());
+
+  if let Some(name) = &spec.name {
+    trigger = trigger.with_name(name);
+  }
+  if let Some(exe) = &spec.exe {
+    trigger = trigger.with_exe(exe);
+  }
+  if let Some(user) = &spec.user {
+    trigger = trigger.with_user(user);
+  }
+
+  trigger
+}
+
+/// Runs the workflow's actions if `matched`, acting on `window` where an action needs one.
+fn run_if(
+  workflow: &Arc<Workflow>,
+  matched: bool,
+  window: Option<Window>,
+) -> impl Future<Output = Result<()>> + Send + use<> {
+  let workflow = matched.then(|| Arc::clone(workflow));
+  async move {
+    match workflow {
+      Some(workflow) => execute(&workflow, window).await,
+      None => Ok(()),
+    }
+  }
+}
+
+/// Runs the actions in order, stopping at the first failure.
+async fn execute(workflow: &Workflow, window: Option<Window>) -> Result<()> {
+  for (index, action) in workflow.actions.iter().enumerate() {
+    perform(action, window).await.map_err(|source| StepError {
+      workflow: workflow.name.clone(),
+      step: index + 1,
+      action: action.label(),
+      source,
+    })?;
+  }
+
+  Ok(())
+}
+
+async fn perform(action: &ActionSpec, window: Option<Window>) -> Result<()> {
+  match action {
+    ActionSpec::Text(text) => KeyboardAction::text(text).run(),
+    ActionSpec::Key(accelerator) => KeyboardAction::from(accelerator.clone()).run(),
+    ActionSpec::MoveMouse(spec) if spec.relative => {
+      MouseAction::move_mouse_relative(spec.x, spec.y).run()
+    }
+    ActionSpec::MoveMouse(spec) => MouseAction::move_mouse(spec.x, spec.y).run(),
+    ActionSpec::Click(button) => MouseAction::click((*button).into()).run(),
+    ActionSpec::Scroll(spec) => MouseAction::scroll(spec.length, spec.axis.into()).run(),
+    ActionSpec::Window(operation) => {
+      let id = target(window)?;
+      match operation {
+        WindowOperation::Close => CloseWindow::from_id(id).run(),
+        WindowOperation::Focus => FocusWindow::from_id(id).run(),
+        WindowOperation::Maximize => MaximizeWindow::from_id(id).run(),
+        WindowOperation::Minimize => MinimizeWindow::from_id(id).run(),
+        WindowOperation::Raise => RaiseWindow::from_id(id).run(),
+        WindowOperation::Restore => RestoreWindow::from_id(id).run(),
+        WindowOperation::Unmaximize => UnmaximizeWindow::from_id(id).run(),
+      }
+    }
+    ActionSpec::MoveWindow(spec) => MoveWindow::from_id(target(window)?, spec.x, spec.y).run(),
+    ActionSpec::ResizeWindow(spec) => {
+      ResizeWindow::from_id(target(window)?, spec.width, spec.height).run()
+    }
+    ActionSpec::Open(spec) => {
+      let mut open = OpenInBrowser::new(&spec.url);
+      if let Some(browser) = &spec.browser {
+        open = open.with_browser(browser);
+      }
+      open.run()
+    }
+    ActionSpec::Run(spec) => {
+      let mut command = RunCommand::new(&spec.command).with_args(&spec.args);
+      if let Some(cwd) = &spec.cwd {
+        command = command.with_current_dir(cwd);
+      }
+      if spec.detach {
+        command = command.detached();
+      }
+      command.run_async().await
+    }
+    ActionSpec::Delay(duration) => {
+      tokio::time::sleep(*duration).await;
+      Ok(())
+    }
+  }
+}
+
+/// The window that triggered the workflow, or the focused one.
+fn target(window: Option<Window>) -> Result<WindowIdentifier> {
+  window
+    .or_else(Window::current)
+    .map(|window| window.id())
+    .ok_or_else(|| Error::WindowStateError("No focused window".to_owned()))
+}
@@ -0,0 +1,404 @@
+use crate::{Action, ActionAsync, Backoff, Error, Result};
+use async_trait::async_trait;
+use derivative::Derivative;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::sleep;
+
+/// An action in a [`Sequence`] or [`Parallel`], named in errors by its name or its
+/// 1-based position.
+#[derive(Derivative, Clone)]
+#[derivative(Debug)]
+struct Step {
+  name: String,
+  #[derivative(Debug = "ignore")]
+  action: Arc<dyn ActionAsync>,
+}
+
+fn step(steps: &[Step], name: Option<String>, action: impl ActionAsync + 'static) -> Step {
+  Step {
+    name: name.unwrap_or_else(|| (steps.len() + 1).to_string()),
+    action: Arc::new(action),
+  }
+}
+
+/// Wraps an error with the step it happened in, joining the names of nested steps.
+fn in_step(step: &str, err: Error) -> Error {
+  match err {
+    Error::StepFailed {
+      step: inner,
+      source,
+    } => Error::StepFailed {
+      step: format!("{step} > {inner}"),
+      source,
+    },
+    source => Error::StepFailed {
+      step: step.to_string(),
+      source: Box::new(source),
+    },
+  }
+}
+
+/// Runs actions one after another, stopping at the first failure.
+///
+/// A failure is reported as [`Error::StepFailed`], naming the step that failed.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// # async fn run() -> Result<()> {
+/// Sequence::new()
+///   .with_named_step("open", RunCommand::new("alacritty").detached())
+///   .with_step(Delay::new(Duration::from_millis(500)))
+///   .with_step(KeyboardAction::text("htop").into_async())
+///   .run_async()
+///   .await
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Sequence {
+  steps: Vec<Step>,
+}
+
+impl Sequence {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds an action, named by its position in errors.
+  pub fn with_step(mut self, action: impl ActionAsync + 'static) -> Self {
+    self.steps.push(step(&self.steps, None, action));
+    self
+  }
+
+  /// Adds an action, named by `name` in errors.
+  pub fn with_named_step(
+    mut self,
+    name: impl Into<String>,
+    action: impl ActionAsync + 'static,
+  ) -> Self {
+    self
+      .steps
+      .push(step(&self.steps, Some(name.into()), action));
+    self
+  }
+}
+
+#[async_trait]
+impl ActionAsync for Sequence {
+  async fn run_async(&self) -> Result<()> {
+    for step in &self.steps {
+      step
+        .action
+        .run_async()
+        .await
+        .map_err(|e| in_step(&step.name, e))?;
+    }
+    Ok(())
+  }
+}
+
+/// Runs actions concurrently and waits for all of them.
+///
+/// If any fail, the failure of the first one added is reported as
+/// [`Error::StepFailed`]. Each action runs in its own task, so synchronous actions
+/// still run one at a time on a single-threaded runtime.
+#[derive(Debug, Clone, Default)]
+pub struct Parallel {
+  steps: Vec<Step>,
+}
+
+impl Parallel {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds an action, named by its position in errors.
+  pub fn with_step(mut self, action: impl ActionAsync + 'static) -> Self {
+    self.steps.push(step(&self.steps, None, action));
+    self
+  }
+
+  /// Adds an action, named by `name` in errors.
+  pub fn with_named_step(
+    mut self,
+    name: impl Into<String>,
+    action: impl ActionAsync + 'static,
+  ) -> Self {
+    self
+      .steps
+      .push(step(&self.steps, Some(name.into()), action));
+    self
+  }
+}
+
+#[async_trait]
+impl ActionAsync for Parallel {
+  async fn run_async(&self) -> Result<()> {
+    // Dropping the set aborts the actions still running, e.g. when timed out.
+    let mut tasks = JoinSet::new();
+    for (index, step) in self.steps.iter().enumerate() {
+      let action = Arc::clone(&step.action);
+      tasks.spawn(async move { (index, action.run_async().await) });
+    }
+
+    let mut failure: Option<(usize, Error)> = None;
+    while let Some(joined) = tasks.join_next().await {
+      let (index, result) = match joined {
+        Ok(done) => done,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(_cancelled) => continue,
+      };
+      if let Err(e) = result
+        && failure.as_ref().is_none_or(|(first, _)| index < *first)
+      {
+        failure = Some((index, e));
+      }
+    }
+
+    match failure {
+      Some((index, e)) => Err(in_step(&self.steps[index].name, e)),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Waits before completing, e.g. to let an application react between two steps.
+#[derive(Debug, Clone, Copy)]
+pub struct Delay {
+  duration: Duration,
+}
+
+impl Delay {
+  pub fn new(duration: Duration) -> Self {
+    Self { duration }
+  }
+}
+
+#[async_trait]
+impl ActionAsync for Delay {
+  async fn run_async(&self) -> Result<()> {
+    sleep(self.duration).await;
+    Ok(())
+  }
+}
+
+/// Runs an action again until it succeeds, up to a number of attempts.
+///
+/// Attempts follow each other immediately unless a [`Backoff`] is set. Fails with the
+/// error of the last attempt.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// let focus = FocusWindow::from_id(WindowIdentifier::new(0x0340_0007)).into_async();
+/// let focus = Retry::new(focus, 5)
+///   .with_backoff(Backoff::new(Duration::from_millis(100), Duration::from_secs(2)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Retry<A> {
+  action: A,
+  attempts: u32,
+  backoff: Backoff,
+}
+
+impl<A> Retry<A> {
+  /// Creates a retry making at most `attempts` attempts, at least one, with no delay
+  /// between them.
+  pub fn new(action: A, attempts: u32) -> Self {
+    Self {
+      action,
+      attempts: attempts.max(1),
+      backoff: Backoff::new(Duration::ZERO, Duration::ZERO),
+    }
+  }
+
+  /// Sets how long to wait between attempts.
+  pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+    self.backoff = backoff;
+    self
+  }
+}
+
+#[async_trait]
+impl<T: Send, A: ActionAsync<T>> ActionAsync<T> for Retry<A> {
+  async fn run_async(&self) -> Result<T> {
+    let mut retry = 0;
+    loop {
+      match self.action.run_async().await {
+        Ok(value) => return Ok(value),
+        Err(e) if retry + 1 >= self.attempts => return Err(e),
+        Err(_) => {}
+      }
+      sleep(self.backoff.delay(retry)).await;
+      retry += 1;
+    }
+  }
+}
+
+/// Fails with [`Error::ActionTimedOut`] if an action does not complete in time.
+///
+/// The action is cancelled at its next await point. Synchronous actions cannot be
+/// interrupted, so they only time out once they return.
+#[derive(Debug, Clone)]
+pub struct Timeout<A> {
+  action: A,
+  duration: Duration,
+}
+
+impl<A> Timeout<A> {
+  pub fn new(action: A, duration: Duration) -> Self {
+    Self { action, duration }
+  }
+}
+
+#[async_trait]
+impl<T: Send, A: ActionAsync<T>> ActionAsync<T> for Timeout<A> {
+  async fn run_async(&self) -> Result<T> {
+    tokio::time::timeout(self.duration, self.action.run_async())
+      .await
+      .map_err(|_| Error::ActionTimedOut(self.duration))?
+  }
+}
+
+/// Runs one action or another depending on a condition checked when it runs.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let dismiss = IfElse::new(
+///   || {
+///     Window::current()
+///       .and_then(|window| window.title())
+///       .is_some_and(|title| title.contains("Update available"))
+///   },
+///   KeyboardAction::key(Key::Escape, Direction::Click).into_async(),
+/// )
+/// .with_else(MouseAction::click(Button::Left).into_async());
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct IfElse {
+  #[derivative(Debug = "ignore")]
+  condition: Box<dyn Fn() -> bool + Send + Sync>,
+  #[derivative(Debug = "ignore")]
+  then: Box<dyn ActionAsync>,
+  #[derivative(Debug = "ignore")]
+  otherwise: Option<Box<dyn ActionAsync>>,
+}
+
+impl IfElse {
+  /// Runs `then` if `condition` returns `true`, and does nothing otherwise.
+  pub fn new<F>(condition: F, then: impl ActionAsync + 'static) -> Self
+  where
+    F: Fn() -> bool + Send + Sync + 'static,
+  {
+    Self {
+      condition: Box::new(condition),
+      then: Box::new(then),
+      otherwise: None,
+    }
+  }
+
+  /// Sets the action run when the condition returns `false`.
+  pub fn with_else(mut self, otherwise: impl ActionAsync + 'static) -> Self {
+    self.otherwise = Some(Box::new(otherwise));
+    self
+  }
+}
+
+#[async_trait]
+impl ActionAsync for IfElse {
+  async fn run_async(&self) -> Result<()> {
+    if (self.condition)() {
+      self.then.run_async().await.map_err(|e| in_step("then", e))
+    } else if let Some(otherwise) = &self.otherwise {
+      otherwise.run_async().await.map_err(|e| in_step("else", e))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Runs an action a number of times in a row, stopping at the first failure.
+///
+/// A failure is reported as [`Error::StepFailed`], naming the 1-based repetition.
+#[derive(Debug, Clone)]
+pub struct Repeat<A> {
+  action: A,
+  times: u32,
+  interval: Duration,
+}
+
+impl<A> Repeat<A> {
+  pub fn new(action: A, times: u32) -> Self {
+    Self {
+      action,
+      times,
+      interval: Duration::ZERO,
+    }
+  }
+
+  /// Sets how long to wait between repetitions.
+  pub fn with_interval(mut self, interval: Duration) -> Self {
+    self.interval = interval;
+    self
+  }
+}
+
+#[async_trait]
+impl<A: ActionAsync> ActionAsync for Repeat<A> {
+  async fn run_async(&self) -> Result<()> {
+    for repetition in 1..=self.times {
+      if repetition > 1 && !self.interval.is_zero() {
+        sleep(self.interval).await;
+      }
+      self
+        .action
+        .run_async()
+        .await
+        .map_err(|e| in_step(&repetition.to_string(), e))?;
+    }
+    Ok(())
+  }
+}
+
+/// Runs a synchronous [`Action`] as an [`ActionAsync`], on tokio's blocking thread pool
+/// so the runtime keeps running while it blocks.
+///
+/// Usually created with [`into_async`](crate::ActionExt::into_async).
+#[derive(Debug)]
+pub struct Blocking<A> {
+  action: Arc<A>,
+}
+
+impl<A> Blocking<A> {
+  pub fn new(action: A) -> Self {
+    Self {
+      action: Arc::new(action),
+    }
+  }
+}
+
+impl<A> Clone for Blocking<A> {
+  fn clone(&self) -> Self {
+    Self {
+      action: Arc::clone(&self.action),
+    }
+  }
+}
+
+#[async_trait]
+impl<T: Send + 'static, A: Action<T> + 'static> ActionAsync<T> for Blocking<A> {
+  async fn run_async(&self) -> Result<T> {
+    let action = Arc::clone(&self.action);
+    match tokio::task::spawn_blocking(move || action.run()).await {
+      Ok(result) => result,
+      Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+      Err(e) => Err(std::io::Error::other(e).into()),
+    }
+  }
+}
@@ -0,0 +1,322 @@
+use crate::{Error, Result};
+use enigo::Key;
+use std::fmt::Display;
+
+/// A key combination such as `Ctrl+Shift+T`, parsed into enigo keys.
+///
+/// Parts are separated by `+` and matched case-insensitively. All parts but the last
+/// must be modifiers:
+///
+/// - `Ctrl`/`Control`, `Alt`/`Option`, `Shift`
+/// - `Super`/`Meta`/`Win`/`Cmd`/`Command` for the Windows, Super or Command key
+/// - `Mod` for the platform's usual shortcut modifier: Command on macOS, Ctrl elsewhere
+///
+/// The last part is either a named key (`Enter`, `Esc`, `Space`, `Tab`, `F1`..`F20`,
+/// arrows, `PageUp`, ...) or a single character. `Plus` names the `+` key.
+///
+/// ```
+/// use automat_core::*;
+///
+/// let accelerator = Accelerator::parse("Super+Shift+N")?;
+/// assert_eq!(accelerator.modifiers(), &[Key::Meta, Key::Shift]);
+/// assert_eq!(accelerator.key(), Key::Unicode('n'));
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accelerator {
+  modifiers: Vec<Key>,
+  key: Key,
+}
+
+impl Accelerator {
+  /// Parses an accelerator string.
+  pub fn parse(accelerator: &str) -> Result<Self> {
+    let invalid =
+      |reason: &str| Error::InvalidAccelerator(format!("`{}`: {}", accelerator, reason));
+
+    let parts = accelerator.split('+').map(str::trim).collect::<Vec<_>>();
+    if parts.iter().any(|part| part.is_empty()) {
+      return Err(invalid("empty key name"));
+    }
+
+    let (last, modifiers) = parts.split_last().ok_or_else(|| invalid("no key"))?;
+
+    let mut parsed = Vec::with_capacity(modifiers.len());
+    for part in modifiers {
+      let modifier =
+        parse_modifier(part).ok_or_else(|| invalid(&format!("`{}` is not a modifier", part)))?;
+      if parsed.contains(&modifier) {
+        return Err(invalid(&format!("modifier `{}` is repeated", part)));
+      }
+      parsed.push(modifier);
+    }
+
+    let key = parse_key(last).ok_or_else(|| invalid(&format!("unknown key `{}`", last)))?;
+
+    Ok(Self {
+      modifiers: parsed,
+      key,
+    })
+  }
+
+  /// Parses a sequence of accelerators separated by whitespace, such as
+  /// `Ctrl+K Ctrl+C`.
+  ///
+  /// Whitespace around a `+` does not separate accelerators, so `Ctrl + K` is a single
+  /// one.
+  pub fn parse_sequence(sequence: &str) -> Result<Vec<Self>> {
+    let mut chords: Vec<String> = Vec::new();
+    for word in sequence.split_whitespace() {
+      match chords.last_mut() {
+        Some(chord) if chord.ends_with('+') || word.starts_with('+') => chord.push_str(word),
+        _ => chords.push(word.to_string()),
+      }
+    }
+
+    if chords.is_empty() {
+      return Err(Error::InvalidAccelerator(format!("`{}`: no key", sequence)));
+    }
+    chords.iter().map(|chord| Self::parse(chord)).collect()
+  }
+
+  /// Modifier keys in the order they were written.
+  pub fn modifiers(&self) -> &[Key] {
+    &self.modifiers
+  }
+
+  /// The non-modifier key. A bare modifier such as `Shift` is also allowed here.
+  pub fn key(&self) -> Key {
+    self.key
+  }
+}
+
+impl Display for Accelerator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for modifier in &self.modifiers {
+      write!(f, "{}+", key_name(*modifier))?;
+    }
+    write!(f, "{}", key_name(self.key))
+  }
+}
+
+impl std::str::FromStr for Accelerator {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self> {
+    Self::parse(s)
+  }
+}
+
+fn parse_modifier(name: &str) -> Option<Key> {
+  match name.to_ascii_lowercase().as_str() {
+    "ctrl" | "control" => Some(Key::Control),
+    "alt" | "option" => Some(Key::Alt),
+    "shift" => Some(Key::Shift),
+    "super" | "meta" | "win" | "cmd" | "command" => Some(Key::Meta),
+    #[cfg(target_os = "macos")]
+    "mod" => Some(Key::Meta),
+    #[cfg(not(target_os = "macos"))]
+    "mod" => Some(Key::Control),
+    _ => None,
+  }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+  if let Some(modifier) = parse_modifier(name) {
+    return Some(modifier);
+  }
+
+  let mut chars = name.chars();
+  if let (Some(c), None) = (chars.next(), chars.next()) {
+    return Some(Key::Unicode(c.to_ascii_lowercase()));
+  }
+
+  let key = match name.to_ascii_lowercase().as_str() {
+    "plus" => Key::Unicode('+'),
+    "enter" | "return" => Key::Return,
+    "esc" | "escape" => Key::Escape,
+    "space" => Key::Space,
+    "tab" => Key::Tab,
+    "backspace" => Key::Backspace,
+    "delete" | "del" => Key::Delete,
+    #[cfg(not(target_os = "macos"))]
+    "insert" | "ins" => Key::Insert,
+    "home" => Key::Home,
+    "end" => Key::End,
+    "pageup" | "pgup" => Key::PageUp,
+    "pagedown" | "pgdn" => Key::PageDown,
+    "up" => Key::UpArrow,
+    "down" => Key::DownArrow,
+    "left" => Key::LeftArrow,
+    "right" => Key::RightArrow,
+    "capslock" => Key::CapsLock,
+    #[cfg(not(target_os = "macos"))]
+    "printscreen" | "print" => Key::PrintScr,
+    #[cfg(not(target_os = "macos"))]
+    "pause" => Key::Pause,
+    "volumeup" => Key::VolumeUp,
+    "volumedown" => Key::VolumeDown,
+    "volumemute" => Key::VolumeMute,
+    "mediaplaypause" => Key::MediaPlayPause,
+    "medianext" => Key::MediaNextTrack,
+    "mediaprev" => Key::MediaPrevTrack,
+    "f1" => Key::F1,
+    "f2" => Key::F2,
+    "f3" => Key::F3,
+    "f4" => Key::F4,
+    "f5" => Key::F5,
+    "f6" => Key::F6,
+    "f7" => Key::F7,
+    "f8" => Key::F8,
+    "f9" => Key::F9,
+    "f10" => Key::F10,
+    "f11" => Key::F11,
+    "f12" => Key::F12,
+    "f13" => Key::F13,
+    "f14" => Key::F14,
+    "f15" => Key::F15,
+    "f16" => Key::F16,
+    "f17" => Key::F17,
+    "f18" => Key::F18,
+    "f19" => Key::F19,
+    "f20" => Key::F20,
+    _ => return None,
+  };
+
+  Some(key)
+}
+
+/// Names a key the way [`parse_key`] accepts it, so `Display` and `FromStr` round-trip.
+fn key_name(key: Key) -> String {
+  let name = match key {
+    Key::Control => "Ctrl",
+    Key::Meta => "Super",
+    Key::Unicode('+') => "Plus",
+    // `parse_key` only lowercases ASCII letters, so only those are shown uppercase.
+    Key::Unicode(c) => return c.to_ascii_uppercase().to_string(),
+    Key::Return => "Enter",
+    Key::Escape => "Esc",
+    Key::UpArrow => "Up",
+    Key::DownArrow => "Down",
+    Key::LeftArrow => "Left",
+    Key::RightArrow => "Right",
+    #[cfg(not(target_os = "macos"))]
+    Key::PrintScr => "Print",
+    Key::MediaNextTrack => "MediaNext",
+    Key::MediaPrevTrack => "MediaPrev",
+    // The remaining keys `parse_key` accepts are named after their variant.
+    other => return format!("{:?}", other),
+  };
+  name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Named keys `parse_key` accepts on every platform, and a few characters.
+  const KEYS: &str = "Plus Enter Return Esc Escape Space Tab Backspace Delete Del Home End \
+    PageUp PgUp PageDown PgDn Up Down Left Right CapsLock VolumeUp VolumeDown VolumeMute \
+    MediaPlayPause MediaNext MediaPrev F1 F12 F20 a Z 7 / é Ctrl Shift";
+
+  #[test]
+  fn display_round_trips() {
+    for key in KEYS.split_whitespace() {
+      for modifiers in [
+        "",
+        "Ctrl+",
+        "Control+Alt+",
+        "Super+Shift+",
+        "Meta+",
+        "Win+Option+",
+      ] {
+        let accelerator = Accelerator::parse(&format!("{modifiers}{key}")).unwrap();
+        let displayed = accelerator.to_string();
+        assert_eq!(
+          Accelerator::parse(&displayed).unwrap(),
+          accelerator,
+          "{displayed}"
+        );
+      }
+    }
+  }
+
+  #[cfg(not(target_os = "macos"))]
+  #[test]
+  fn display_round_trips_pc_keys() {
+    for key in ["Insert", "Ins", "PrintScreen", "Print", "Pause"] {
+      let accelerator = Accelerator::parse(key).unwrap();
+      assert_eq!(
+        Accelerator::parse(&accelerator.to_string()).unwrap(),
+        accelerator
+      );
+    }
+  }
+
+  #[test]
+  fn display_uses_canonical_names() {
+    let display = |accelerator: &str| Accelerator::parse(accelerator).unwrap().to_string();
+    assert_eq!(display("control+shift+t"), "Ctrl+Shift+T");
+    assert_eq!(display("meta + return"), "Super+Enter");
+    assert_eq!(display("Ctrl+Plus"), "Ctrl+Plus");
+    assert_eq!(display("escape"), "Esc");
+  }
+
+  #[test]
+  fn parse_keeps_modifier_order() {
+    let accelerator = Accelerator::parse("Shift+Ctrl+F5").unwrap();
+    assert_eq!(accelerator.modifiers(), &[Key::Shift, Key::Control]);
+    assert_eq!(accelerator.key(), Key::F5);
+  }
+
+  #[test]
+  fn parse_sequence_splits_on_whitespace() {
+    let sequence = Accelerator::parse_sequence("  Ctrl+K\tCtrl + C  Shift +Tab ").unwrap();
+    let expected = ["Ctrl+K", "Ctrl+C", "Shift+Tab"].map(|a| Accelerator::parse(a).unwrap());
+    assert_eq!(sequence, expected);
+  }
+
+  #[test]
+  fn parse_sequence_rejects_invalid() {
+    assert!(Accelerator::parse_sequence("   ").is_err());
+    assert!(Accelerator::parse_sequence("Ctrl+K Hyper+C").is_err());
+  }
+
+  #[test]
+  fn mod_is_the_platform_shortcut_modifier() {
+    let expected = if cfg!(target_os = "macos") {
+      Key::Meta
+    } else {
+      Key::Control
+    };
+    assert_eq!(
+      Accelerator::parse("Mod+S").unwrap().modifiers(),
+      &[expected]
+    );
+    assert_eq!(
+      Accelerator::parse("Cmd+S").unwrap().modifiers(),
+      &[Key::Meta]
+    );
+  }
+
+  #[test]
+  fn parse_rejects_invalid() {
+    for accelerator in [
+      "",
+      "Ctrl+",
+      "Ctrl++",
+      "A+B",
+      "Ctrl+Control+A",
+      "Hyper+A",
+      "Ctrl+Foo",
+    ] {
+      assert!(
+        matches!(
+          Accelerator::parse(accelerator),
+          Err(Error::InvalidAccelerator(_))
+        ),
+        "{accelerator}"
+      );
+    }
+  }
+}
@@ -1,6 +1,6 @@
 use crate::actions::with_enigo;
-use crate::{Action, Result};
-use enigo::{Direction, Key, Keyboard};
+use crate::{Accelerator, Action, Result};
+use enigo::{Direction, Enigo, InputError, Key, Keyboard};
 
 /// A keyboard action that can either type text or press/release a key.
 pub struct KeyboardAction(KeyboardActionKind);
@@ -11,10 +11,15 @@ pub enum KeyboardActionKind {
   Text(String),
   /// Press or release a specific key.
   Key(Key, Direction),
+  /// Press key combinations one after another.
+  Shortcut(Vec<Accelerator>),
 }
 
 impl KeyboardAction {
-  /// Creates a new keyboard action that types the given text.
+  /// Creates a new keyboard action that types the given text all at once.
+  ///
+  /// Use [`TypeText`](crate::TypeText) for applications that drop characters typed
+  /// this fast.
   pub fn text<S: Into<String>>(text: S) -> Self {
     Self(KeyboardActionKind::Text(text.into()))
   }
@@ -24,18 +29,81 @@ impl KeyboardAction {
     Self(KeyboardActionKind::Key(key, direction))
   }
 
+  /// Creates a new keyboard action that presses a key combination, such as
+  /// `Ctrl+Shift+T`. See [`Accelerator`] for the accepted syntax.
+  ///
+  /// The modifiers are pressed in the order they are written, then the key is tapped
+  /// and the modifiers are released in reverse order, even if pressing a key failed.
+  ///
+  /// ```no_run
+  /// use automat_core::*;
+  ///
+  /// // Reopens the last closed tab.
+  /// KeyboardAction::shortcut("Mod+Shift+T")?.run()?;
+  /// # Ok::<(), Error>(())
+  /// ```
+  pub fn shortcut(shortcut: &str) -> Result<Self> {
+    Ok(Self::from(Accelerator::parse(shortcut)?))
+  }
+
+  /// Creates a new keyboard action that presses key combinations one after another,
+  /// separated by whitespace, such as `Ctrl+K Ctrl+C`.
+  ///
+  /// Each combination is pressed like a [`shortcut`](Self::shortcut), and the sequence
+  /// stops at the first one that fails.
+  pub fn sequence(sequence: &str) -> Result<Self> {
+    Ok(Self(KeyboardActionKind::Shortcut(
+      Accelerator::parse_sequence(sequence)?,
+    )))
+  }
+
   /// Returns the kind of keyboard action.
   pub fn kind(&self) -> &KeyboardActionKind {
     &self.0
   }
 }
 
+impl From<Accelerator> for KeyboardAction {
+  fn from(accelerator: Accelerator) -> Self {
+    Self(KeyboardActionKind::Shortcut(vec![accelerator]))
+  }
+}
+
 impl Action for KeyboardAction {
   fn run(&self) -> Result<()> {
     with_enigo(|e| match self.kind() {
       KeyboardActionKind::Text(text) => e.text(&text),
       KeyboardActionKind::Key(key, direction) => e.key(*key, *direction),
+      KeyboardActionKind::Shortcut(accelerators) => accelerators
+        .iter()
+        .try_for_each(|accelerator| press(e, accelerator)),
     })
     .map_err(Into::into)
   }
 }
+
+/// Presses the modifiers in order, taps the key and releases the modifiers in reverse,
+/// even if a key press failed, so no modifier is left held down.
+fn press(e: &mut Enigo, accelerator: &Accelerator) -> std::result::Result<(), InputError> {
+  let mut pressed = Vec::new();
+  let mut result = Ok(());
+
+  for modifier in accelerator.modifiers() {
+    result = e.key(*modifier, Direction::Press);
+    if result.is_err() {
+      break;
+    }
+    pressed.push(*modifier);
+  }
+
+  if result.is_ok() {
+    result = e.key(accelerator.key(), Direction::Click);
+  }
+
+  for modifier in pressed.into_iter().rev() {
+    let released = e.key(modifier, Direction::Release);
+    result = result.and(released);
+  }
+
+  result
+}
@@ -2,11 +2,17 @@ use enigo::{Enigo, Settings};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
+mod accelerator;
 mod keyboard;
 mod mouse;
+mod mouse_gesture;
+mod typing;
 
+pub use accelerator::*;
 pub use keyboard::*;
 pub use mouse::*;
+pub use mouse_gesture::*;
+pub use typing::*;
 
 static ENIGO: Lazy<Mutex<Enigo>> =
   Lazy::new(|| Mutex::new(Enigo::new(&Settings::default()).unwrap()));
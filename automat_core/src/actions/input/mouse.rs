@@ -98,3 +98,8 @@ impl Action for MouseAction {
     .map_err(Into::into)
   }
 }
+
+/// Returns the position of the mouse cursor in screen coordinates.
+pub fn mouse_position() -> Result<(i32, i32)> {
+  with_enigo(|e| e.location()).map_err(Into::into)
+}
@@ -0,0 +1,313 @@
+use crate::{
+  ActionAsync, Error, MouseAction, Result, Window, WindowIdentifier, mouse_position, with_enigo,
+};
+use async_trait::async_trait;
+use enigo::{Button, Coordinate, Direction, Mouse};
+use std::time::Duration;
+use tokio::time::{Instant, sleep};
+
+/// Time between two positions of an animated move.
+const FRAME: Duration = Duration::from_millis(8);
+
+/// A point of a window, to place the cursor relative to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+  #[default]
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  BottomRight,
+  Center,
+}
+
+/// Where a [`MouseGesture`] moves the cursor, resolved when it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseTarget {
+  /// Wherever the cursor is.
+  Cursor,
+  /// Absolute screen coordinates.
+  Screen { x: i32, y: i32 },
+  /// An offset from an anchor of a window's outer frame. Positive offsets go right and
+  /// down, so 20 pixels left of the top-right corner is `x: -20`.
+  Window {
+    /// The window, or the focused one if `None`.
+    window: Option<WindowIdentifier>,
+    anchor: Anchor,
+    x: i32,
+    y: i32,
+  },
+}
+
+impl MouseTarget {
+  /// An offset from an anchor of the focused window.
+  pub fn focused_window(anchor: Anchor, x: i32, y: i32) -> Self {
+    Self::Window {
+      window: None,
+      anchor,
+      x,
+      y,
+    }
+  }
+
+  /// An offset from an anchor of the given window.
+  pub fn window(window: &Window, anchor: Anchor, x: i32, y: i32) -> Self {
+    Self::Window {
+      window: Some(window.id()),
+      anchor,
+      x,
+      y,
+    }
+  }
+
+  /// The screen coordinates of the target, or `None` for the cursor.
+  fn resolve(self) -> Result<Option<(i32, i32)>> {
+    match self {
+      Self::Cursor => Ok(None),
+      Self::Screen { x, y } => Ok(Some((x, y))),
+      Self::Window {
+        window,
+        anchor,
+        x,
+        y,
+      } => {
+        let window = match window {
+          Some(id) => Window::new(id),
+          None => Window::current()
+            .ok_or_else(|| Error::WindowStateError("No focused window".to_owned()))?,
+        };
+        let geometry = window.geometry().ok_or_else(|| {
+          Error::WindowGeometryError(format!("Cannot read geometry of window {:?}", window.id()))
+        })?;
+
+        let (width, height) = (geometry.width as i32, geometry.height as i32);
+        let (left, top) = match anchor {
+          Anchor::TopLeft => (0, 0),
+          Anchor::TopRight => (width, 0),
+          Anchor::BottomLeft => (0, height),
+          Anchor::BottomRight => (width, height),
+          Anchor::Center => (width / 2, height / 2),
+        };
+        Ok(Some((geometry.x + left + x, geometry.y + top + y)))
+      }
+    }
+  }
+}
+
+impl From<(i32, i32)> for MouseTarget {
+  fn from((x, y): (i32, i32)) -> Self {
+    Self::Screen { x, y }
+  }
+}
+
+/// How an animated move progresses over its duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+  Linear,
+  /// Starts slowly and speeds up.
+  EaseIn,
+  /// Starts quickly and slows down.
+  EaseOut,
+  /// Starts and ends slowly, like a hand moving a mouse.
+  #[default]
+  EaseInOut,
+}
+
+impl Easing {
+  /// Maps the elapsed fraction of the duration to the covered fraction of the distance.
+  fn apply(self, t: f64) -> f64 {
+    match self {
+      Self::Linear => t,
+      Self::EaseIn => t * t * t,
+      Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+      Self::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+      Self::EaseInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GestureKind {
+  Move,
+  Drag { from: MouseTarget, button: Button },
+  Click { button: Button, count: u32 },
+}
+
+/// A mouse action that takes time: an animated move, a drag or a multi-click.
+///
+/// Created by [`MouseAction::move_to`], [`MouseAction::drag`],
+/// [`MouseAction::double_click`] and [`MouseAction::triple_click`]. It runs as an
+/// [`ActionAsync`], so the runtime keeps running while the cursor moves.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// # async fn run() -> Result<()> {
+/// // Double-clicks 20 pixels left of and below the focused window's top-right corner.
+/// MouseAction::double_click(Button::Left)
+///   .at(MouseTarget::focused_window(Anchor::TopRight, -20, 20))
+///   .with_duration(Duration::from_millis(300))
+///   .run_async()
+///   .await?;
+///
+/// MouseAction::drag((100, 100), (400, 300), Button::Left)
+///   .with_easing(Easing::Linear)
+///   .run_async()
+///   .await
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MouseGesture {
+  kind: GestureKind,
+  to: MouseTarget,
+  duration: Duration,
+  easing: Easing,
+}
+
+impl MouseGesture {
+  fn new(kind: GestureKind, to: MouseTarget, duration: Duration) -> Self {
+    Self {
+      kind,
+      to,
+      duration,
+      easing: Easing::default(),
+    }
+  }
+
+  /// Sets where the gesture ends: the destination of a move or drag, or where the
+  /// clicks happen.
+  pub fn at(mut self, target: impl Into<MouseTarget>) -> Self {
+    self.to = target.into();
+    self
+  }
+
+  /// Sets how long moving the cursor takes. Zero moves it at once.
+  pub fn with_duration(mut self, duration: Duration) -> Self {
+    self.duration = duration;
+    self
+  }
+
+  /// Sets how the cursor speeds up and slows down while moving.
+  pub fn with_easing(mut self, easing: Easing) -> Self {
+    self.easing = easing;
+    self
+  }
+
+  /// Moves the cursor from where it is to `to`.
+  async fn glide(&self, to: Option<(i32, i32)>) -> Result<()> {
+    let Some((x, y)) = to else {
+      return Ok(());
+    };
+    if self.duration.is_zero() {
+      return move_cursor(x, y);
+    }
+
+    let (start_x, start_y) = mouse_position()?;
+    let start = Instant::now();
+    loop {
+      let t = (start.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0);
+      let progress = self.easing.apply(t);
+      let lerp = |from: i32, to: i32| from + ((to - from) as f64 * progress).round() as i32;
+      move_cursor(lerp(start_x, x), lerp(start_y, y))?;
+
+      if t >= 1.0 {
+        return Ok(());
+      }
+      sleep(FRAME).await;
+    }
+  }
+}
+
+#[async_trait]
+impl ActionAsync for MouseGesture {
+  async fn run_async(&self) -> Result<()> {
+    let to = self.to.resolve()?;
+    match self.kind {
+      GestureKind::Move => self.glide(to).await,
+      GestureKind::Drag { from, button } => {
+        if let Some((x, y)) = from.resolve()? {
+          move_cursor(x, y)?;
+        }
+        with_enigo(|e| e.button(button, Direction::Press))?;
+        // Release the button even if the move failed, so it isn't left held down.
+        let moved = self.glide(to).await;
+        let released = with_enigo(|e| e.button(button, Direction::Release)).map_err(Into::into);
+        moved.and(released)
+      }
+      GestureKind::Click { button, count } => {
+        self.glide(to).await?;
+        // Well within the interval, so the clicks count as one double or triple click.
+        let gap = double_click_interval() / 4;
+        for click in 0..count {
+          if click > 0 {
+            sleep(gap).await;
+          }
+          with_enigo(|e| e.button(button, Direction::Click))?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+fn move_cursor(x: i32, y: i32) -> Result<()> {
+  with_enigo(|e| e.move_mouse(x, y, Coordinate::Abs)).map_err(Into::into)
+}
+
+impl MouseAction {
+  /// Creates a move of the cursor to a target, animated with
+  /// [`with_duration`](MouseGesture::with_duration).
+  pub fn move_to(target: impl Into<MouseTarget>) -> MouseGesture {
+    MouseGesture::new(GestureKind::Move, target.into(), Duration::ZERO)
+  }
+
+  /// Creates a drag: presses the button at `from`, moves to `to` over 250 ms by
+  /// default, and releases it.
+  pub fn drag(
+    from: impl Into<MouseTarget>,
+    to: impl Into<MouseTarget>,
+    button: Button,
+  ) -> MouseGesture {
+    let kind = GestureKind::Drag {
+      from: from.into(),
+      button,
+    };
+    MouseGesture::new(kind, to.into(), Duration::from_millis(250))
+  }
+
+  /// Creates a double click where the cursor is, or [`at`](MouseGesture::at) a target.
+  pub fn double_click(button: Button) -> MouseGesture {
+    let kind = GestureKind::Click { button, count: 2 };
+    MouseGesture::new(kind, MouseTarget::Cursor, Duration::ZERO)
+  }
+
+  /// Creates a triple click where the cursor is, or [`at`](MouseGesture::at) a target.
+  pub fn triple_click(button: Button) -> MouseGesture {
+    let kind = GestureKind::Click { button, count: 3 };
+    MouseGesture::new(kind, MouseTarget::Cursor, Duration::ZERO)
+  }
+}
+
+/// The longest time between clicks that still makes them a double click.
+///
+/// On Linux this is the desktop's XSETTINGS `Net/DoubleClickTime`, or the
+/// `multiClickTime` X resource, and 400 ms, the GTK default, when neither is set.
+pub fn double_click_interval() -> Duration {
+  #[cfg(target_os = "windows")]
+  {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
+    Duration::from_millis(u64::from(unsafe { GetDoubleClickTime() }))
+  }
+
+  #[cfg(target_os = "macos")]
+  {
+    use objc::{class, msg_send, sel, sel_impl};
+    let interval: f64 = unsafe { msg_send![class!(NSEvent), doubleClickInterval] };
+    Duration::from_secs_f64(interval)
+  }
+
+  #[cfg(target_os = "linux")]
+  {
+    crate::linux::double_click_time().unwrap_or(Duration::from_millis(400))
+  }
+}
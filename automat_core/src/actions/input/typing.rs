@@ -0,0 +1,124 @@
+use crate::actions::with_enigo;
+use crate::{ActionAsync, Result};
+use async_trait::async_trait;
+use enigo::{Direction, Enigo, InputError, Key, Keyboard};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Characters after which [`TypeText`] pauses, like a person finishing a phrase.
+const PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\n'];
+
+/// Action that types text one character at a time, like a person would.
+///
+/// Some applications, notably Electron apps and remote desktop clients, drop characters
+/// when a whole string arrives at once through [`KeyboardAction::text`]. This types
+/// each character on its own, waiting between keystrokes without blocking the runtime.
+///
+/// Characters are typed as text by default. One that cannot be, or every one with
+/// [`with_unicode_keys`](Self::with_unicode_keys), is typed as a [`Key::Unicode`] key
+/// press instead.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// # async fn run() -> Result<()> {
+/// TypeText::new("Hello, world!")
+///   .with_delay(Duration::from_millis(40))
+///   .with_jitter(Duration::from_millis(30))
+///   .with_punctuation_pause(Duration::from_millis(200))
+///   .run_async()
+///   .await
+/// # }
+/// ```
+///
+/// [`KeyboardAction::text`]: crate::KeyboardAction::text
+#[derive(Debug, Clone)]
+pub struct TypeText {
+  text: String,
+  delay: Duration,
+  jitter: Duration,
+  punctuation_pause: Duration,
+  unicode_keys: bool,
+}
+
+impl TypeText {
+  /// Creates an action typing `text` with 30 ms between keystrokes.
+  pub fn new(text: impl Into<String>) -> Self {
+    Self {
+      text: text.into(),
+      delay: Duration::from_millis(30),
+      jitter: Duration::ZERO,
+      punctuation_pause: Duration::ZERO,
+      unicode_keys: false,
+    }
+  }
+
+  /// Sets the time between keystrokes.
+  pub fn with_delay(mut self, delay: Duration) -> Self {
+    self.delay = delay;
+    self
+  }
+
+  /// Sets the most that is randomly added to or removed from each delay.
+  pub fn with_jitter(mut self, jitter: Duration) -> Self {
+    self.jitter = jitter;
+    self
+  }
+
+  /// Sets an extra pause after punctuation and line breaks.
+  pub fn with_punctuation_pause(mut self, pause: Duration) -> Self {
+    self.punctuation_pause = pause;
+    self
+  }
+
+  /// Types every character as a [`Key::Unicode`] key press rather than as text.
+  pub fn with_unicode_keys(mut self, unicode_keys: bool) -> Self {
+    self.unicode_keys = unicode_keys;
+    self
+  }
+
+  /// Returns the text that will be typed.
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  /// The wait after typing `c`.
+  fn pause_after(&self, c: char) -> Duration {
+    let jitter = self.jitter.as_secs_f64() * (fastrand::f64() * 2.0 - 1.0);
+    let delay = Duration::from_secs_f64((self.delay.as_secs_f64() + jitter).max(0.0));
+
+    if PUNCTUATION.contains(&c) {
+      delay + self.punctuation_pause
+    } else {
+      delay
+    }
+  }
+
+  fn type_char(&self, e: &mut Enigo, c: char) -> std::result::Result<(), InputError> {
+    if !self.unicode_keys && e.text(c.encode_utf8(&mut [0; 4])).is_ok() {
+      return Ok(());
+    }
+
+    let key = match c {
+      '\n' => Key::Return,
+      '\t' => Key::Tab,
+      c => Key::Unicode(c),
+    };
+    e.key(key, Direction::Click)
+  }
+}
+
+#[async_trait]
+impl ActionAsync for TypeText {
+  async fn run_async(&self) -> Result<()> {
+    let mut chars = self.text.chars().peekable();
+    while let Some(c) = chars.next() {
+      with_enigo(|e| self.type_char(e, c))?;
+      if chars.peek().is_some() {
+        sleep(self.pause_after(c)).await;
+      }
+    }
+    Ok(())
+  }
+}
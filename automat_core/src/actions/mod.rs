@@ -1,14 +1,18 @@
+mod combinators;
 mod input;
 
 mod open_in_browser;
+mod run_command;
 mod windows;
 
 use super::error::Result;
 use async_trait::async_trait;
 
 pub use enigo::{Axis, Button, Coordinate, Direction, Key};
+pub use combinators::*;
 pub use input::*;
 pub use open_in_browser::*;
+pub use run_command::*;
 pub use windows::*;
 
 /// Represents a synchronous action that can be executed.
@@ -35,3 +39,15 @@ pub trait ActionAsync<T = ()>: Send + Sync {
   /// Returns a [`Result`] indicating whether the action completed successfully.
   async fn run_async(&self) -> Result<T>;
 }
+
+/// Adapts a synchronous [`Action`] to an [`ActionAsync`], for places that expect one
+/// such as a [`Sequence`].
+pub trait ActionExt<T = ()>: Action<T> + Sized + 'static {
+  /// Wraps the action so it runs on tokio's blocking thread pool instead of the calling
+  /// task.
+  fn into_async(self) -> Blocking<Self> {
+    Blocking::new(self)
+  }
+}
+
+impl<T, A: Action<T> + 'static> ActionExt<T> for A {}
@@ -0,0 +1,142 @@
+use crate::{Action, ActionAsync, Error, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+/// Action that runs an external program.
+///
+/// By default the action waits for the program to exit and fails if it exits with a
+/// non-zero status. Use [`detached`](Self::detached) to start it and return immediately.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// RunCommand::new("notify-send")
+///   .with_args(["Backup", "Finished copying files"])
+///   .run()
+///   .unwrap();
+///
+/// // Launch an application without waiting for it to close.
+/// RunCommand::new("alacritty").detached().run().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RunCommand {
+  program: String,
+  args: Vec<String>,
+  current_dir: Option<PathBuf>,
+  detached: bool,
+}
+
+impl RunCommand {
+  /// Creates a new action that runs the given program, looked up in `PATH`.
+  pub fn new(program: impl Into<String>) -> Self {
+    Self {
+      program: program.into(),
+      args: Vec::new(),
+      current_dir: None,
+      detached: false,
+    }
+  }
+
+  /// Adds an argument passed to the program.
+  pub fn with_arg(mut self, arg: impl Into<String>) -> Self {
+    self.args.push(arg.into());
+    self
+  }
+
+  /// Adds multiple arguments passed to the program.
+  pub fn with_args<I, S>(mut self, args: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.args.extend(args.into_iter().map(Into::into));
+    self
+  }
+
+  /// Runs the program in the given working directory.
+  pub fn with_current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+    self.current_dir = Some(dir.as_ref().to_path_buf());
+    self
+  }
+
+  /// Starts the program without waiting for it to exit.
+  pub fn detached(mut self) -> Self {
+    self.detached = true;
+    self
+  }
+
+  /// Returns the program that will be run.
+  pub fn program(&self) -> &str {
+    &self.program
+  }
+
+  /// Returns the arguments passed to the program.
+  pub fn args(&self) -> &[String] {
+    &self.args
+  }
+
+  /// Returns the working directory, if one was set.
+  pub fn current_dir(&self) -> Option<&Path> {
+    self.current_dir.as_deref()
+  }
+
+  /// Returns whether the action returns without waiting for the program to exit.
+  pub fn is_detached(&self) -> bool {
+    self.detached
+  }
+
+  fn spawn_error(&self, err: std::io::Error) -> Error {
+    Error::CommandFailed(format!("cannot start `{}`: {}", self.program, err))
+  }
+
+  fn check_status(&self, status: std::process::ExitStatus) -> Result<()> {
+    if status.success() {
+      Ok(())
+    } else {
+      Err(Error::CommandFailed(format!(
+        "`{}` exited with {}",
+        self.program, status
+      )))
+    }
+  }
+}
+
+impl Action for RunCommand {
+  fn run(&self) -> Result<()> {
+    let mut command = std::process::Command::new(&self.program);
+    command.args(&self.args);
+    if let Some(dir) = &self.current_dir {
+      command.current_dir(dir);
+    }
+
+    let mut child = command.spawn().map_err(|e| self.spawn_error(e))?;
+    if self.detached {
+      // Reap the child once it exits so it doesn't linger as a zombie.
+      std::thread::spawn(move || child.wait());
+      return Ok(());
+    }
+
+    let status = child.wait()?;
+    self.check_status(status)
+  }
+}
+
+#[async_trait]
+impl ActionAsync for RunCommand {
+  async fn run_async(&self) -> Result<()> {
+    let mut command = tokio::process::Command::new(&self.program);
+    command.args(&self.args);
+    if let Some(dir) = &self.current_dir {
+      command.current_dir(dir);
+    }
+
+    // Tokio reaps dropped children in the background.
+    let mut child = command.spawn().map_err(|e| self.spawn_error(e))?;
+    if self.detached {
+      return Ok(());
+    }
+
+    let status = child.wait().await?;
+    self.check_status(status)
+  }
+}
@@ -1,4 +1,3 @@
-use windows::Win32::Foundation::{LPARAM, WPARAM};
 use crate::{Action, Result, Window, WindowIdentifier};
 
 /// Closes a window.
@@ -38,7 +37,7 @@ impl Action for CloseWindow {
 
 #[cfg(target_os = "windows")]
 fn close_window(window_id: WindowIdentifier) -> Result<()> {
-  use windows::Win32::Foundation::HWND;
+  use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
   use windows::Win32::UI::WindowsAndMessaging::{PostMessageW, WM_CLOSE};
 
   unsafe {
@@ -57,27 +56,13 @@ fn close_window(window_id: WindowIdentifier) -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn close_window(window_id: WindowIdentifier) -> Result<()> {
-  use std::ffi::CString;
-  use std::ptr;
+  use crate::linux::{WM_DELETE_WINDOW, WM_PROTOCOLS, with_connection};
   use x11::xlib::*;
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(crate::Error::WindowStateError(
-        "Failed to open X display".to_string(),
-      ));
-    }
-
+  with_connection(|conn| unsafe {
     let window = window_id.as_u64();
-    let root = XDefaultRootWindow(display);
-
-    // Get the WM_DELETE_WINDOW and WM_PROTOCOLS atoms
-    let wm_protocols = CString::new("WM_PROTOCOLS").unwrap();
-    let wm_delete_window = CString::new("WM_DELETE_WINDOW").unwrap();
-
-    let atom_wm_protocols = XInternAtom(display, wm_protocols.as_ptr(), 0);
-    let atom_wm_delete_window = XInternAtom(display, wm_delete_window.as_ptr(), 0);
+    let atom_wm_protocols = conn.atom(WM_PROTOCOLS);
+    let atom_wm_delete_window = conn.atom(WM_DELETE_WINDOW);
 
     // Send ClientMessage to close the window
     let mut event: XClientMessageEvent = std::mem::zeroed();
@@ -89,18 +74,15 @@ fn close_window(window_id: WindowIdentifier) -> Result<()> {
     event.data.set_long(1, CurrentTime as i64);
 
     XSendEvent(
-      display,
+      conn.display(),
       window,
       0,
       NoEventMask,
       &mut event as *mut XClientMessageEvent as *mut XEvent,
     );
 
-    XFlush(display);
-    XCloseDisplay(display);
-  }
-
-  Ok(())
+    XFlush(conn.display());
+  })
 }
 
 #[cfg(target_os = "macos")]
@@ -0,0 +1,132 @@
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Activates a window: restores it if minimized, raises it and gives it keyboard focus.
+///
+/// Window managers may refuse to steal focus from the user, so the window can end up
+/// only highlighted in the taskbar.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let editor = list_windows()
+///   .unwrap()
+///   .into_iter()
+///   .find(|window| window.title().is_some_and(|title| title.ends_with("- Visual Studio Code")));
+///
+/// if let Some(editor) = editor {
+///   FocusWindow::for_window(editor).run().unwrap();
+/// }
+/// ```
+pub struct FocusWindow {
+  window_id: WindowIdentifier,
+}
+
+impl FocusWindow {
+  /// Focuses a specific window.
+  pub fn for_window(window: Window) -> Self {
+    Self {
+      window_id: window.id(),
+    }
+  }
+
+  /// Focuses the currently focused window.
+  ///
+  /// Useful to bring it back to front after it was raised above by another window.
+  pub fn current() -> Self {
+    Self::try_current().expect("No focused window")
+  }
+
+  /// Attempts to focus the currently focused window.
+  pub fn try_current() -> Option<Self> {
+    Window::current().map(Self::for_window)
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier) -> Self {
+    Self { window_id }
+  }
+}
+
+impl Action for FocusWindow {
+  fn run(&self) -> Result<()> {
+    focus_window(self.window_id)
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn focus_window(window_id: WindowIdentifier) -> Result<()> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::{
+    IsIconic, SW_RESTORE, SetForegroundWindow, ShowWindow,
+  };
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    if IsIconic(hwnd).as_bool() {
+      let _ = ShowWindow(hwnd, SW_RESTORE);
+    }
+
+    if !SetForegroundWindow(hwnd).as_bool() {
+      return Err(crate::Error::WindowStateError(
+        "Failed to focus window".to_string(),
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn focus_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::linux::{NET_ACTIVE_WINDOW, SOURCE_PAGER, with_connection};
+  use x11::xlib::*;
+
+  with_connection(|conn| unsafe {
+    let window = window_id.as_u64();
+
+    if conn.supports(NET_ACTIVE_WINDOW) {
+      // The window manager deiconifies, raises and focuses the window, switching
+      // desktops if needed
+      conn.send_wm_message(
+        window,
+        NET_ACTIVE_WINDOW,
+        [SOURCE_PAGER, CurrentTime as i64, 0, 0, 0],
+      );
+    } else {
+      XMapRaised(conn.display(), window);
+      XSetInputFocus(conn.display(), window, RevertToParent, CurrentTime);
+      XFlush(conn.display());
+    }
+  })
+}
+
+#[cfg(target_os = "macos")]
+fn focus_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::Error;
+  use objc2::rc::autoreleasepool;
+  use objc2::runtime::AnyObject;
+  use objc2::{msg_send, sel};
+  use objc2_app_kit::{NSApplication, NSWindow};
+  use objc2_foundation::NSArray;
+  use std::ptr;
+
+  autoreleasepool(|_| unsafe {
+    let app: *mut NSApplication = msg_send![class!(NSApplication), sharedApplication];
+    let windows: *mut NSArray<NSWindow> = msg_send![app, windows];
+    let count: usize = msg_send![windows, count];
+
+    for i in 0..count {
+      let window: *mut NSWindow = msg_send![windows, objectAtIndex: i];
+      let win_number: i64 = msg_send![window, windowNumber];
+
+      if win_number as u64 == window_id.as_u64() {
+        let _: () = msg_send![window, deminiaturize: ptr::null::<AnyObject>()];
+        let _: () = msg_send![window, makeKeyAndOrderFront: ptr::null::<AnyObject>()];
+        let _: () = msg_send![app, activateIgnoringOtherApps: true];
+        return Ok(());
+      }
+    }
+
+    Err(Error::WindowStateError("Window not found".to_string()))
+  })
+}
@@ -50,53 +50,28 @@ fn maximize_window(window_id: WindowIdentifier) -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn maximize_window(window_id: WindowIdentifier) -> Result<()> {
-  use std::ffi::CString;
-  use std::ptr;
-  use x11::xlib::*;
-
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(crate::Error::WindowStateError(
-        "Failed to open X display".to_string(),
-      ));
-    }
-
-    let window = window_id.as_u64();
-    let root = XDefaultRootWindow(display);
-
-    // Get the _NET_WM_STATE and _NET_WM_STATE_MAXIMIZED atoms
-    let net_wm_state = CString::new("_NET_WM_STATE").unwrap();
-    let net_wm_state_max_horz = CString::new("_NET_WM_STATE_MAXIMIZED_HORZ").unwrap();
-    let net_wm_state_max_vert = CString::new("_NET_WM_STATE_MAXIMIZED_VERT").unwrap();
-
-    let atom_net_wm_state = XInternAtom(display, net_wm_state.as_ptr(), 0);
-    let atom_max_horz = XInternAtom(display, net_wm_state_max_horz.as_ptr(), 0);
-    let atom_max_vert = XInternAtom(display, net_wm_state_max_vert.as_ptr(), 0);
-
-    // Send ClientMessage to maximize the window
-    let mut event: XClientMessageEvent = std::mem::zeroed();
-    event.type_ = ClientMessage;
-    event.window = window;
-    event.message_type = atom_net_wm_state;
-    event.format = 32;
-    event.data.set_long(0, 1); // _NET_WM_STATE_ADD
-    event.data.set_long(1, atom_max_horz as i64);
-    event.data.set_long(2, atom_max_vert as i64);
-
-    XSendEvent(
-      display,
-      root,
-      0,
-      SubstructureNotifyMask | SubstructureRedirectMask,
-      &mut event as *mut XClientMessageEvent as *mut XEvent,
+  use crate::linux::{
+    NET_WM_STATE, NET_WM_STATE_ADD, NET_WM_STATE_MAXIMIZED_HORZ, NET_WM_STATE_MAXIMIZED_VERT,
+    with_connection,
+  };
+
+  with_connection(|conn| {
+    let atom_max_horz = conn.atom(NET_WM_STATE_MAXIMIZED_HORZ);
+    let atom_max_vert = conn.atom(NET_WM_STATE_MAXIMIZED_VERT);
+
+    // Ask the window manager to add both maximized states
+    conn.send_wm_message(
+      window_id.as_u64(),
+      NET_WM_STATE,
+      [
+        NET_WM_STATE_ADD,
+        atom_max_horz as i64,
+        atom_max_vert as i64,
+        0,
+        0,
+      ],
     );
-
-    XFlush(display);
-    XCloseDisplay(display);
-  }
-
-  Ok(())
+  })
 }
 
 #[cfg(target_os = "macos")]
@@ -50,28 +50,16 @@ fn minimize_window(window_id: WindowIdentifier) -> Result<()> {
 
 #[cfg(target_os = "linux")]
 fn minimize_window(window_id: WindowIdentifier) -> Result<()> {
-  use std::ffi::CString;
-  use std::ptr;
+  use crate::linux::with_connection;
   use x11::xlib::*;
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(crate::Error::WindowStateError(
-        "Failed to open X display".to_string(),
-      ));
-    }
-
-    let window = window_id.as_u64();
-    let screen = XDefaultScreen(display);
+  with_connection(|conn| unsafe {
+    let screen = XDefaultScreen(conn.display());
 
     // Iconify the window
-    XIconifyWindow(display, window, screen);
-    XFlush(display);
-    XCloseDisplay(display);
-  }
-
-  Ok(())
+    XIconifyWindow(conn.display(), window_id.as_u64(), screen);
+    XFlush(conn.display());
+  })
 }
 
 #[cfg(target_os = "macos")]
@@ -1,9 +1,23 @@
 mod close_window;
+mod focus_window;
 mod maximize_window;
 mod minimize_window;
+mod move_window;
+mod raise_window;
+mod resize_window;
+mod restore_window;
+mod set_window_geometry;
 mod set_window_title;
+mod unmaximize_window;
 
 pub use close_window::*;
+pub use focus_window::*;
 pub use maximize_window::*;
 pub use minimize_window::*;
+pub use move_window::*;
+pub use raise_window::*;
+pub use resize_window::*;
+pub use restore_window::*;
+pub use set_window_geometry::*;
 pub use set_window_title::*;
+pub use unmaximize_window::*;
@@ -0,0 +1,61 @@
+use crate::actions::windows::set_window_geometry::set_window_geometry;
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Moves a window so its outer frame's top-left corner is at the given screen position.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// MoveWindow::current(100, 100).run().unwrap();
+/// ```
+pub struct MoveWindow {
+  window_id: WindowIdentifier,
+  x: i32,
+  y: i32,
+}
+
+impl MoveWindow {
+  /// Moves a specific window.
+  pub fn for_window(window: Window, x: i32, y: i32) -> Self {
+    Self {
+      window_id: window.id(),
+      x,
+      y,
+    }
+  }
+
+  /// Moves the currently focused window.
+  ///
+  /// Panics if no window is focused. Use `try_current` for a fallible version.
+  pub fn current(x: i32, y: i32) -> Self {
+    Self::try_current(x, y).expect("No focused window")
+  }
+
+  /// Attempts to move the currently focused window.
+  ///
+  /// Returns `None` if no window is focused.
+  pub fn try_current(x: i32, y: i32) -> Option<Self> {
+    Window::current().map(|window| Self::for_window(window, x, y))
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier, x: i32, y: i32) -> Self {
+    Self { window_id, x, y }
+  }
+
+  /// Returns the target window identifier.
+  pub fn window_id(&self) -> WindowIdentifier {
+    self.window_id
+  }
+
+  /// Returns the position the window will be moved to.
+  pub fn position(&self) -> (i32, i32) {
+    (self.x, self.y)
+  }
+}
+
+impl Action for MoveWindow {
+  fn run(&self) -> Result<()> {
+    set_window_geometry(self.window_id, Some((self.x, self.y)), None)
+  }
+}
@@ -0,0 +1,114 @@
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Brings a window to the top of the stacking order without giving it focus.
+///
+/// Use [`FocusWindow`](crate::FocusWindow) to also activate it.
+pub struct RaiseWindow {
+  window_id: WindowIdentifier,
+}
+
+impl RaiseWindow {
+  /// Raises a specific window.
+  pub fn for_window(window: Window) -> Self {
+    Self {
+      window_id: window.id(),
+    }
+  }
+
+  /// Raises the currently focused window.
+  pub fn current() -> Self {
+    Self::try_current().expect("No focused window")
+  }
+
+  /// Attempts to raise the currently focused window.
+  pub fn try_current() -> Option<Self> {
+    Window::current().map(Self::for_window)
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier) -> Self {
+    Self { window_id }
+  }
+}
+
+impl Action for RaiseWindow {
+  fn run(&self) -> Result<()> {
+    raise_window(self.window_id)
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn raise_window(window_id: WindowIdentifier) -> Result<()> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::{
+    HWND_TOP, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SetWindowPos,
+  };
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    SetWindowPos(
+      hwnd,
+      Some(HWND_TOP),
+      0,
+      0,
+      0,
+      0,
+      SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+    )
+    .map_err(|e| crate::Error::WindowStateError(format!("Failed to raise window: {}", e)))?;
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn raise_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::linux::{NET_RESTACK_WINDOW, SOURCE_PAGER, with_connection};
+  use x11::xlib::*;
+
+  with_connection(|conn| unsafe {
+    let window = window_id.as_u64();
+
+    // Window managers reparent clients into frames, so raising the client itself
+    // usually has no visible effect. Ask the window manager when it can do it.
+    if conn.supports(NET_RESTACK_WINDOW) {
+      conn.send_wm_message(
+        window,
+        NET_RESTACK_WINDOW,
+        [SOURCE_PAGER, 0, Above as i64, 0, 0],
+      );
+    } else {
+      XRaiseWindow(conn.display(), window);
+      XFlush(conn.display());
+    }
+  })
+}
+
+#[cfg(target_os = "macos")]
+fn raise_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::Error;
+  use objc2::rc::autoreleasepool;
+  use objc2::runtime::AnyObject;
+  use objc2::{msg_send, sel};
+  use objc2_app_kit::{NSApplication, NSWindow};
+  use objc2_foundation::NSArray;
+  use std::ptr;
+
+  autoreleasepool(|_| unsafe {
+    let app: *mut NSApplication = msg_send![class!(NSApplication), sharedApplication];
+    let windows: *mut NSArray<NSWindow> = msg_send![app, windows];
+    let count: usize = msg_send![windows, count];
+
+    for i in 0..count {
+      let window: *mut NSWindow = msg_send![windows, objectAtIndex: i];
+      let win_number: i64 = msg_send![window, windowNumber];
+
+      if win_number as u64 == window_id.as_u64() {
+        let _: () = msg_send![window, orderFront: ptr::null::<AnyObject>()];
+        return Ok(());
+      }
+    }
+
+    Err(Error::WindowStateError("Window not found".to_string()))
+  })
+}
@@ -0,0 +1,65 @@
+use crate::actions::windows::set_window_geometry::set_window_geometry;
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Resizes a window's outer frame, keeping its top-left corner in place.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// ResizeWindow::current(1280, 720).run().unwrap();
+/// ```
+pub struct ResizeWindow {
+  window_id: WindowIdentifier,
+  width: u32,
+  height: u32,
+}
+
+impl ResizeWindow {
+  /// Resizes a specific window.
+  pub fn for_window(window: Window, width: u32, height: u32) -> Self {
+    Self {
+      window_id: window.id(),
+      width,
+      height,
+    }
+  }
+
+  /// Resizes the currently focused window.
+  ///
+  /// Panics if no window is focused. Use `try_current` for a fallible version.
+  pub fn current(width: u32, height: u32) -> Self {
+    Self::try_current(width, height).expect("No focused window")
+  }
+
+  /// Attempts to resize the currently focused window.
+  ///
+  /// Returns `None` if no window is focused.
+  pub fn try_current(width: u32, height: u32) -> Option<Self> {
+    Window::current().map(|window| Self::for_window(window, width, height))
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier, width: u32, height: u32) -> Self {
+    Self {
+      window_id,
+      width,
+      height,
+    }
+  }
+
+  /// Returns the target window identifier.
+  pub fn window_id(&self) -> WindowIdentifier {
+    self.window_id
+  }
+
+  /// Returns the size the window will be resized to.
+  pub fn size(&self) -> (u32, u32) {
+    (self.width, self.height)
+  }
+}
+
+impl Action for ResizeWindow {
+  fn run(&self) -> Result<()> {
+    set_window_geometry(self.window_id, None, Some((self.width, self.height)))
+  }
+}
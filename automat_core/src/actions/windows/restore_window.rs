@@ -0,0 +1,108 @@
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Restores a minimized or maximized window to its normal state.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// for window in list_windows().unwrap() {
+///   if window.state().is_ok_and(|state| state.minimized) {
+///     RestoreWindow::for_window(window).run().unwrap();
+///   }
+/// }
+/// ```
+pub struct RestoreWindow {
+  window_id: WindowIdentifier,
+}
+
+impl RestoreWindow {
+  /// Restores a specific window.
+  pub fn for_window(window: Window) -> Self {
+    Self {
+      window_id: window.id(),
+    }
+  }
+
+  /// Restores the currently focused window.
+  pub fn current() -> Self {
+    Self::try_current().expect("No focused window")
+  }
+
+  /// Attempts to restore the currently focused window.
+  pub fn try_current() -> Option<Self> {
+    Window::current().map(Self::for_window)
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier) -> Self {
+    Self { window_id }
+  }
+}
+
+impl Action for RestoreWindow {
+  fn run(&self) -> Result<()> {
+    restore_window(self.window_id)
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn restore_window(window_id: WindowIdentifier) -> Result<()> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::{SW_RESTORE, ShowWindow};
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    let _ = ShowWindow(hwnd, SW_RESTORE);
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn restore_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::actions::windows::unmaximize_window::unmaximize_window;
+  use crate::linux::with_connection;
+  use x11::xlib::*;
+
+  unmaximize_window(window_id)?;
+
+  with_connection(|conn| unsafe {
+    // Mapping an iconified window moves it back to the normal state
+    XMapWindow(conn.display(), window_id.as_u64());
+    XFlush(conn.display());
+  })
+}
+
+#[cfg(target_os = "macos")]
+fn restore_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::Error;
+  use objc2::rc::autoreleasepool;
+  use objc2::runtime::AnyObject;
+  use objc2::{msg_send, sel};
+  use objc2_app_kit::{NSApplication, NSWindow};
+  use objc2_foundation::NSArray;
+  use std::ptr;
+
+  autoreleasepool(|_| unsafe {
+    let app: *mut NSApplication = msg_send![class!(NSApplication), sharedApplication];
+    let windows: *mut NSArray<NSWindow> = msg_send![app, windows];
+    let count: usize = msg_send![windows, count];
+
+    for i in 0..count {
+      let window: *mut NSWindow = msg_send![windows, objectAtIndex: i];
+      let win_number: i64 = msg_send![window, windowNumber];
+
+      if win_number as u64 == window_id.as_u64() {
+        let _: () = msg_send![window, deminiaturize: ptr::null::<AnyObject>()];
+
+        let zoomed: bool = msg_send![window, isZoomed];
+        if zoomed {
+          let _: () = msg_send![window, zoom: ptr::null::<AnyObject>()];
+        }
+        return Ok(());
+      }
+    }
+
+    Err(Error::WindowStateError("Window not found".to_string()))
+  })
+}
@@ -0,0 +1,185 @@
+use crate::{Action, Result, Window, WindowGeometry, WindowIdentifier};
+
+/// Moves and resizes a window in one step.
+///
+/// The geometry describes the outer frame, including decorations, the same way
+/// [`Window::geometry`] reports it. Maximized windows may ignore the request until they
+/// are restored.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// // Snap the current window to the left half of a 1920x1080 screen.
+/// SetWindowGeometry::current(WindowGeometry::new(0, 0, 960, 1080)).run().unwrap();
+/// ```
+pub struct SetWindowGeometry {
+  window_id: WindowIdentifier,
+  geometry: WindowGeometry,
+}
+
+impl SetWindowGeometry {
+  /// Sets the geometry of a specific window.
+  pub fn for_window(window: Window, geometry: WindowGeometry) -> Self {
+    Self {
+      window_id: window.id(),
+      geometry,
+    }
+  }
+
+  /// Sets the geometry of the currently focused window.
+  ///
+  /// Panics if no window is focused. Use `try_current` for a fallible version.
+  pub fn current(geometry: WindowGeometry) -> Self {
+    Self::try_current(geometry).expect("No focused window")
+  }
+
+  /// Attempts to set the geometry of the currently focused window.
+  ///
+  /// Returns `None` if no window is focused.
+  pub fn try_current(geometry: WindowGeometry) -> Option<Self> {
+    Window::current().map(|window| Self::for_window(window, geometry))
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier, geometry: WindowGeometry) -> Self {
+    Self {
+      window_id,
+      geometry,
+    }
+  }
+
+  /// Returns the target window identifier.
+  pub fn window_id(&self) -> WindowIdentifier {
+    self.window_id
+  }
+
+  /// Returns the geometry that will be applied.
+  pub fn geometry(&self) -> WindowGeometry {
+    self.geometry
+  }
+}
+
+impl Action for SetWindowGeometry {
+  fn run(&self) -> Result<()> {
+    set_window_geometry(
+      self.window_id,
+      Some(self.geometry.position()),
+      Some(self.geometry.size()),
+    )
+  }
+}
+
+#[cfg(target_os = "windows")]
+/// Moves and/or resizes the outer frame of a window. `None` keeps the current value.
+pub(crate) fn set_window_geometry(
+  window_id: WindowIdentifier,
+  position: Option<(i32, i32)>,
+  size: Option<(u32, u32)>,
+) -> Result<()> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::{
+    SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SetWindowPos,
+  };
+
+  let mut flags = SWP_NOZORDER | SWP_NOACTIVATE;
+  if position.is_none() {
+    flags |= SWP_NOMOVE;
+  }
+  if size.is_none() {
+    flags |= SWP_NOSIZE;
+  }
+
+  let (x, y) = position.unwrap_or_default();
+  let (width, height) = size.unwrap_or_default();
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    SetWindowPos(hwnd, None, x, y, width as i32, height as i32, flags).map_err(|e| {
+      crate::Error::WindowGeometryError(format!("Failed to set window position: {}", e))
+    })
+  }
+}
+
+#[cfg(target_os = "linux")]
+/// Moves and/or resizes the outer frame of a window. `None` keeps the current value.
+///
+/// Uses `_NET_MOVERESIZE_WINDOW` when the window manager supports it, so the request is
+/// interpreted in client coordinates regardless of the window's gravity. Otherwise the
+/// window is configured directly.
+pub(crate) fn set_window_geometry(
+  window_id: WindowIdentifier,
+  position: Option<(i32, i32)>,
+  size: Option<(u32, u32)>,
+) -> Result<()> {
+  use crate::linux::{NET_MOVERESIZE_WINDOW, SOURCE_PAGER, with_connection};
+  use crate::window::frame_extents;
+  use std::mem::zeroed;
+  use x11::xlib::*;
+
+  with_connection(|conn| unsafe {
+    let window = window_id.as_u64();
+    let mut attributes: XWindowAttributes = zeroed();
+    if XGetWindowAttributes(conn.display(), window, &mut attributes) == 0 {
+      return Err(crate::Error::WindowGeometryError(
+        "Window not found".to_string(),
+      ));
+    }
+
+    // Convert the outer frame to the client area the X server works with.
+    let extents = frame_extents(conn, window);
+    let position = position.map(|(x, y)| (x + extents.left as i32, y + extents.top as i32));
+    let size = size.map(|(width, height)| {
+      (
+        width.saturating_sub(extents.left + extents.right).max(1),
+        height.saturating_sub(extents.top + extents.bottom).max(1),
+      )
+    });
+
+    if conn.supports(NET_MOVERESIZE_WINDOW) {
+      // Gravity in bits 0-7, which fields are set in bits 8-11, and source indication
+      // in bits 12-13.
+      let mut flags = StaticGravity as i64 | SOURCE_PAGER << 12;
+      if position.is_some() {
+        flags |= 1 << 8 | 1 << 9;
+      }
+      if size.is_some() {
+        flags |= 1 << 10 | 1 << 11;
+      }
+
+      let (x, y) = position.unwrap_or_default();
+      let (width, height) = size.unwrap_or_default();
+      conn.send_wm_message(
+        window,
+        NET_MOVERESIZE_WINDOW,
+        [flags, x.into(), y.into(), width.into(), height.into()],
+      );
+    } else {
+      match (position, size) {
+        (Some((x, y)), Some((width, height))) => {
+          XMoveResizeWindow(conn.display(), window, x, y, width, height);
+        }
+        (Some((x, y)), None) => {
+          XMoveWindow(conn.display(), window, x, y);
+        }
+        (None, Some((width, height))) => {
+          XResizeWindow(conn.display(), window, width, height);
+        }
+        (None, None) => {}
+      }
+      XFlush(conn.display());
+    }
+
+    Ok(())
+  })?
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub(crate) fn set_window_geometry(
+  _window_id: WindowIdentifier,
+  _position: Option<(i32, i32)>,
+  _size: Option<(u32, u32)>,
+) -> Result<()> {
+  Err(crate::Error::WindowGeometryError(
+    "Moving and resizing windows is not supported on this platform".to_string(),
+  ))
+}
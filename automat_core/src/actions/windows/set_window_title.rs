@@ -91,28 +91,17 @@ fn set_window_title_impl(window_id: WindowIdentifier, title: &str) -> Result<()>
 
 #[cfg(target_os = "linux")]
 fn set_window_title_impl(window_id: WindowIdentifier, title: &str) -> Result<()> {
+  use crate::linux::with_connection;
   use std::ffi::CString;
-  use std::ptr;
   use x11::xlib::*;
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(crate::Error::WindowTitleError(
-        "Failed to open X display".to_string(),
-      ));
-    }
+  let title_cstr = CString::new(title)
+    .map_err(|_| crate::Error::WindowTitleError("Invalid title string".to_string()))?;
 
-    let window = window_id.as_u64();
-    let title_cstr = CString::new(title)
-      .map_err(|_| crate::Error::WindowTitleError("Invalid title string".to_string()))?;
-
-    XStoreName(display, window, title_cstr.as_ptr());
-    XFlush(display);
-    XCloseDisplay(display);
-
-    Ok(())
-  }
+  with_connection(|conn| unsafe {
+    XStoreName(conn.display(), window_id.as_u64(), title_cstr.as_ptr());
+    XFlush(conn.display());
+  })
 }
 
 #[cfg(target_os = "macos")]
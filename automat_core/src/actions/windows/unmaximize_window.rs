@@ -0,0 +1,112 @@
+use crate::{Action, Result, Window, WindowIdentifier};
+
+/// Returns a maximized window to its previous size.
+///
+/// Does nothing if the window is not maximized.
+pub struct UnmaximizeWindow {
+  window_id: WindowIdentifier,
+}
+
+impl UnmaximizeWindow {
+  /// Unmaximizes a specific window.
+  pub fn for_window(window: Window) -> Self {
+    Self {
+      window_id: window.id(),
+    }
+  }
+
+  /// Unmaximizes the currently focused window.
+  pub fn current() -> Self {
+    Self::try_current().expect("No focused window")
+  }
+
+  /// Attempts to unmaximize the currently focused window.
+  pub fn try_current() -> Option<Self> {
+    Window::current().map(Self::for_window)
+  }
+
+  /// Creates an action for a specific window identifier.
+  pub fn from_id(window_id: WindowIdentifier) -> Self {
+    Self { window_id }
+  }
+}
+
+impl Action for UnmaximizeWindow {
+  fn run(&self) -> Result<()> {
+    unmaximize_window(self.window_id)
+  }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn unmaximize_window(window_id: WindowIdentifier) -> Result<()> {
+  use windows::Win32::Foundation::HWND;
+  use windows::Win32::UI::WindowsAndMessaging::{IsZoomed, SW_RESTORE, ShowWindow};
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    if IsZoomed(hwnd).as_bool() {
+      let _ = ShowWindow(hwnd, SW_RESTORE);
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn unmaximize_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::linux::{
+    NET_WM_STATE, NET_WM_STATE_MAXIMIZED_HORZ, NET_WM_STATE_MAXIMIZED_VERT, NET_WM_STATE_REMOVE,
+    SOURCE_PAGER, with_connection,
+  };
+
+  with_connection(|conn| {
+    let atom_max_horz = conn.atom(NET_WM_STATE_MAXIMIZED_HORZ);
+    let atom_max_vert = conn.atom(NET_WM_STATE_MAXIMIZED_VERT);
+
+    // Ask the window manager to remove both maximized states
+    conn.send_wm_message(
+      window_id.as_u64(),
+      NET_WM_STATE,
+      [
+        NET_WM_STATE_REMOVE,
+        atom_max_horz as i64,
+        atom_max_vert as i64,
+        SOURCE_PAGER,
+        0,
+      ],
+    );
+  })
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn unmaximize_window(window_id: WindowIdentifier) -> Result<()> {
+  use crate::Error;
+  use objc2::rc::autoreleasepool;
+  use objc2::runtime::AnyObject;
+  use objc2::{msg_send, sel};
+  use objc2_app_kit::{NSApplication, NSWindow};
+  use objc2_foundation::NSArray;
+  use std::ptr;
+
+  autoreleasepool(|_| unsafe {
+    let app: *mut NSApplication = msg_send![class!(NSApplication), sharedApplication];
+    let windows: *mut NSArray<NSWindow> = msg_send![app, windows];
+    let count: usize = msg_send![windows, count];
+
+    for i in 0..count {
+      let window: *mut NSWindow = msg_send![windows, objectAtIndex: i];
+      let win_number: i64 = msg_send![window, windowNumber];
+
+      if win_number as u64 == window_id.as_u64() {
+        // `zoom:` toggles, so only call it on a zoomed window
+        let zoomed: bool = msg_send![window, isZoomed];
+        if zoomed {
+          let _: () = msg_send![window, zoom: ptr::null::<AnyObject>()];
+        }
+        return Ok(());
+      }
+    }
+
+    Err(Error::WindowStateError("Window not found".to_string()))
+  })
+}
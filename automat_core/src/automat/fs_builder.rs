@@ -1,12 +1,41 @@
-use crate::{FileSystemTrigger, Result, TriggerContext};
-use notify::{Config, Event};
+use crate::{FileSystemTrigger, FsEvent, FsEventKind, Result, TriggerContext, WatchBackend};
+use notify::Config;
 use std::future::Future;
 use std::path::Path;
+use std::time::Duration;
 
 /// Builder for configuring a `FileSystemTrigger` before adding it to `Automat`.
+///
+/// ```rust no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// let trigger = FileSystemBuilder::new()
+///   .watch_recursive("./my-project")
+///   .watch_with_backend(
+///     "/mnt/share/drop",
+///     false,
+///     WatchBackend::Poll { interval: Duration::from_secs(5) },
+///   )
+///   .glob("*.rs")
+///   .glob("!target/**")
+///   .event_kinds([FsEventKind::Create, FsEventKind::Modify])
+///   .gitignore(true)
+///   .debounce(Duration::from_millis(300))
+///   .on_event_blocking(|ctx| {
+///     println!("{} changed", ctx.data.path().display());
+///     Ok(())
+///   });
+/// ```
 pub struct FileSystemBuilder {
-  paths: Vec<(std::path::PathBuf, bool)>,
+  paths: Vec<(std::path::PathBuf, bool, Option<WatchBackend>)>,
   config: Option<Config>,
+  backend: WatchBackend,
+  debounce: Option<Duration>,
+  globs: Vec<String>,
+  excludes: Vec<String>,
+  kinds: Option<Vec<FsEventKind>>,
+  gitignore: bool,
 }
 
 impl FileSystemBuilder {
@@ -15,12 +44,31 @@ impl FileSystemBuilder {
     Self {
       paths: Vec::new(),
       config: None,
+      backend: WatchBackend::default(),
+      debounce: None,
+      globs: Vec::new(),
+      excludes: Vec::new(),
+      kinds: None,
+      gitignore: false,
     }
   }
 
   /// Adds a path to watch for file system changes.
   pub fn watch<P: AsRef<Path>>(mut self, path: P, recursive: bool) -> Self {
-    self.paths.push((path.as_ref().to_path_buf(), recursive));
+    self.paths.push((path.as_ref().to_path_buf(), recursive, None));
+    self
+  }
+
+  /// Adds a path to watch with its own backend, such as polling for a network share.
+  pub fn watch_with_backend<P: AsRef<Path>>(
+    mut self,
+    path: P,
+    recursive: bool,
+    backend: WatchBackend,
+  ) -> Self {
+    self
+      .paths
+      .push((path.as_ref().to_path_buf(), recursive, Some(backend)));
     self
   }
 
@@ -31,7 +79,7 @@ impl FileSystemBuilder {
     P: AsRef<Path>,
   {
     for (path, recursive) in paths {
-      self.paths.push((path.as_ref().to_path_buf(), recursive));
+      self.paths.push((path.as_ref().to_path_buf(), recursive, None));
     }
     self
   }
@@ -52,41 +100,96 @@ impl FileSystemBuilder {
     self
   }
 
+  /// Sets how paths added without a backend of their own are watched.
+  pub fn backend(mut self, backend: WatchBackend) -> Self {
+    self.backend = backend;
+    self
+  }
+
+  /// Coalesces events on the same paths until they have been quiet for `delay`.
+  pub fn debounce(mut self, delay: Duration) -> Self {
+    self.debounce = Some(delay);
+    self
+  }
+
+  /// Only reports paths matching the glob, such as `*.rs`, or skips them if the
+  /// pattern starts with `!`, such as `!target/**`.
+  pub fn glob(mut self, pattern: &str) -> Self {
+    self.globs.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports paths matching the glob.
+  pub fn include(self, pattern: &str) -> Self {
+    self.glob(pattern)
+  }
+
+  /// Never reports paths matching the glob.
+  pub fn exclude(mut self, pattern: &str) -> Self {
+    self.excludes.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports events of the given kinds.
+  pub fn event_kinds(mut self, kinds: impl IntoIterator<Item = FsEventKind>) -> Self {
+    self.kinds = Some(kinds.into_iter().collect());
+    self
+  }
+
+  /// Skips paths ignored by git.
+  pub fn gitignore(mut self, respect: bool) -> Self {
+    self.gitignore = respect;
+    self
+  }
+
   /// Sets the callback and builds the `FileSystemTrigger`.
   pub fn on_event<F, Fut>(self, callback: F) -> FileSystemTrigger
   where
-    F: Fn(TriggerContext<Result<Event>>) -> Fut + Send + Sync + 'static,
+    F: Fn(TriggerContext<FsEvent>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<()>> + Send + 'static,
   {
-    let mut trigger = FileSystemTrigger::new(callback);
-
-    if let Some(config) = self.config {
-      trigger = trigger.with_config(config);
-    }
-
-    for (path, recursive) in self.paths {
-      trigger = trigger.watch_path(path, recursive);
-    }
-
-    trigger
+    self.build(FileSystemTrigger::new(callback))
   }
 
   /// Sets a synchronous (blocking) callback and builds the `FileSystemTrigger`.
   pub fn on_event_blocking<F>(self, callback: F) -> FileSystemTrigger
   where
-    F: Fn(TriggerContext<Result<Event>>) -> Result<()> + Send + Sync + 'static,
+    F: Fn(TriggerContext<FsEvent>) -> Result<()> + Send + Sync + 'static,
   {
-    let mut trigger = FileSystemTrigger::new_blocking(callback);
+    self.build(FileSystemTrigger::new_blocking(callback))
+  }
 
+  fn build(self, mut trigger: FileSystemTrigger) -> FileSystemTrigger {
     if let Some(config) = self.config {
       trigger = trigger.with_config(config);
     }
 
-    for (path, recursive) in self.paths {
-      trigger = trigger.watch_path(path, recursive);
+    if let Some(delay) = self.debounce {
+      trigger = trigger.with_debounce(delay);
+    }
+
+    if let Some(kinds) = self.kinds {
+      trigger = trigger.with_event_kinds(kinds);
+    }
+
+    for pattern in &self.globs {
+      trigger = trigger.include(pattern);
+    }
+
+    for pattern in &self.excludes {
+      trigger = trigger.exclude(pattern);
+    }
+
+    for (path, recursive, backend) in self.paths {
+      trigger = match backend {
+        Some(backend) => trigger.watch_path_with_backend(path, recursive, backend),
+        None => trigger.watch_path(path, recursive),
+      };
     }
 
     trigger
+      .with_backend(self.backend)
+      .respect_gitignore(self.gitignore)
   }
 
   /// Returns the number of paths currently configured to watch.
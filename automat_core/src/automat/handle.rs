@@ -0,0 +1,302 @@
+use crate::automat::runner::supervise;
+use crate::{Error, ErrorHandler, Result, Supervision, Trigger};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, timeout};
+use tokio_util::sync::CancellationToken;
+
+/// Identifier of a trigger managed by an [`AutomatHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TriggerId(u64);
+
+impl TriggerId {
+  pub fn as_u64(&self) -> u64 {
+    self.0
+  }
+}
+
+impl Display for TriggerId {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "#{}", self.0)
+  }
+}
+
+/// Lifecycle state of a supervised trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerState {
+  /// The trigger is running.
+  Running,
+  /// The trigger was paused and will not run until resumed.
+  Paused,
+  /// The trigger stopped and is waiting for its restart backoff to elapse.
+  Restarting,
+  /// The trigger stopped for good.
+  Stopped,
+}
+
+/// Snapshot of a trigger returned by [`AutomatHandle::list`].
+#[derive(Debug, Clone)]
+pub struct TriggerInfo {
+  pub id: TriggerId,
+  pub name: String,
+  pub state: TriggerState,
+}
+
+/// Control channels handed to the supervisor of a single trigger.
+pub(crate) struct TriggerControl {
+  pub(crate) id: TriggerId,
+  pub(crate) paused: watch::Receiver<bool>,
+  /// Cancelled when the trigger is removed or the runner shuts down.
+  pub(crate) cancel: CancellationToken,
+}
+
+struct TriggerEntry {
+  name: String,
+  state: TriggerState,
+  paused: watch::Sender<bool>,
+  cancel: CancellationToken,
+  task: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct Registry {
+  next_id: u64,
+  entries: BTreeMap<TriggerId, TriggerEntry>,
+}
+
+pub(crate) struct HandleInner {
+  registry: Mutex<Registry>,
+  pub(crate) shutdown: CancellationToken,
+  error_handler: Option<ErrorHandler>,
+  supervision: Supervision,
+}
+
+impl HandleInner {
+  pub(crate) fn set_state(&self, id: TriggerId, state: TriggerState) {
+    if let Some(entry) = self.registry.lock().entries.get_mut(&id) {
+      entry.state = state;
+    }
+  }
+
+  pub(crate) fn report(&self, err: Error, label: &str) {
+    if let Some(ref handler) = self.error_handler {
+      handler(err);
+    } else {
+      eprintln!("{}: {}", label, err);
+    }
+  }
+}
+
+/// Cloneable handle to a running [`Automat`](crate::Automat).
+///
+/// Returned by [`Automat::spawn`](crate::Automat::spawn). Triggers can be added, removed,
+/// paused and resumed while the runner is active.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<()> {
+/// let handle = Automat::new().spawn();
+///
+/// let id = handle.add(Box::new(IntervalTrigger::new_blocking(
+///   Duration::from_secs(1),
+///   |_| Ok(()),
+/// )));
+///
+/// handle.pause(id)?;
+/// for info in handle.list() {
+///   println!("{} {} {:?}", info.id, info.name, info.state);
+/// }
+/// handle.resume(id)?;
+///
+/// handle.shutdown().await;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AutomatHandle {
+  inner: Arc<HandleInner>,
+}
+
+impl std::fmt::Debug for AutomatHandle {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("AutomatHandle")
+      .field("triggers_count", &self.inner.registry.lock().entries.len())
+      .field("shutdown", &self.inner.shutdown.is_cancelled())
+      .finish()
+  }
+}
+
+impl AutomatHandle {
+  pub(crate) fn new(error_handler: Option<ErrorHandler>, supervision: Supervision) -> Self {
+    Self {
+      inner: Arc::new(HandleInner {
+        registry: Mutex::new(Registry::default()),
+        shutdown: CancellationToken::new(),
+        error_handler,
+        supervision,
+      }),
+    }
+  }
+
+  /// Adds a trigger using the runner-wide supervision and starts it immediately.
+  pub fn add(&self, trigger: Box<dyn Trigger>) -> TriggerId {
+    self.add_supervised(trigger, self.inner.supervision)
+  }
+
+  /// Adds a trigger with its own supervision and starts it immediately.
+  pub fn add_supervised(&self, trigger: Box<dyn Trigger>, supervision: Supervision) -> TriggerId {
+    let (paused_tx, paused_rx) = watch::channel(false);
+    let cancel = self.inner.shutdown.child_token();
+
+    let mut registry = self.inner.registry.lock();
+    let id = TriggerId(registry.next_id);
+    registry.next_id += 1;
+
+    let control = TriggerControl {
+      id,
+      paused: paused_rx,
+      cancel: cancel.clone(),
+    };
+
+    registry.entries.insert(
+      id,
+      TriggerEntry {
+        name: trigger.name(),
+        state: TriggerState::Running,
+        paused: paused_tx,
+        cancel,
+        task: None,
+      },
+    );
+
+    let task = tokio::spawn(supervise(trigger, supervision, self.inner.clone(), control));
+
+    if let Some(entry) = registry.entries.get_mut(&id) {
+      entry.task = Some(task);
+    }
+
+    id
+  }
+
+  /// Stops a trigger and removes it from the runner.
+  ///
+  /// Waits up to two seconds for the trigger to stop before aborting it.
+  pub async fn remove(&self, id: TriggerId) -> Result<()> {
+    let entry = self
+      .inner
+      .registry
+      .lock()
+      .entries
+      .remove(&id)
+      .ok_or(Error::TriggerNotFound(id))?;
+
+    entry.cancel.cancel();
+    if let Some(task) = entry.task {
+      Self::join_tasks(vec![task]).await;
+    }
+
+    Ok(())
+  }
+
+  /// Pauses a trigger. It keeps its internal state and continues where it left off
+  /// when resumed.
+  pub fn pause(&self, id: TriggerId) -> Result<()> {
+    self.set_paused(id, true)
+  }
+
+  /// Resumes a paused trigger.
+  pub fn resume(&self, id: TriggerId) -> Result<()> {
+    self.set_paused(id, false)
+  }
+
+  fn set_paused(&self, id: TriggerId, paused: bool) -> Result<()> {
+    let registry = self.inner.registry.lock();
+    let entry = registry
+      .entries
+      .get(&id)
+      .ok_or(Error::TriggerNotFound(id))?;
+
+    entry.paused.send_replace(paused);
+    Ok(())
+  }
+
+  /// Returns the current state of a trigger, or `None` if no trigger has that id.
+  pub fn state(&self, id: TriggerId) -> Option<TriggerState> {
+    self.inner.registry.lock().entries.get(&id).map(|e| e.state)
+  }
+
+  /// Lists all triggers known to the runner, ordered by id.
+  pub fn list(&self) -> Vec<TriggerInfo> {
+    self
+      .inner
+      .registry
+      .lock()
+      .entries
+      .iter()
+      .map(|(id, entry)| TriggerInfo {
+        id: *id,
+        name: entry.name.clone(),
+        state: entry.state,
+      })
+      .collect()
+  }
+
+  /// Reports an error through the runner's [`on_error`](crate::Automat::on_error)
+  /// handler, or prints it if none is set.
+  ///
+  /// Lets code driving the runner report its own errors next to the triggers' errors.
+  pub fn report_error(&self, err: Error) {
+    self.inner.report(err, "Automat");
+  }
+
+  /// Returns true once the runner has shut down.
+  pub fn is_shutdown(&self) -> bool {
+    self.inner.shutdown.is_cancelled()
+  }
+
+  /// Waits until the runner shuts down, either through [`shutdown`](Self::shutdown)
+  /// or because a trigger escalated.
+  pub async fn wait(&self) {
+    self.inner.shutdown.cancelled().await;
+  }
+
+  /// Stops every trigger and shuts the runner down.
+  ///
+  /// Waits up to two seconds for triggers to stop before aborting them.
+  pub async fn shutdown(&self) {
+    self.inner.shutdown.cancel();
+
+    let tasks = self
+      .inner
+      .registry
+      .lock()
+      .entries
+      .values_mut()
+      .filter_map(|entry| entry.task.take())
+      .collect();
+
+    Self::join_tasks(tasks).await;
+  }
+
+  async fn join_tasks(mut tasks: Vec<JoinHandle<()>>) {
+    let joined = timeout(Duration::from_secs(2), async {
+      for task in &mut tasks {
+        let _ = task.await;
+      }
+    })
+    .await;
+
+    if joined.is_err() {
+      for task in tasks.into_iter().filter(|task| !task.is_finished()) {
+        task.abort();
+        let _ = task.await;
+      }
+    }
+  }
+}
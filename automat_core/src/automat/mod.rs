@@ -1,12 +1,16 @@
 mod fs_builder;
+mod handle;
 mod runner;
+mod supervisor;
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 pub use fs_builder::FileSystemBuilder;
+pub use handle::{AutomatHandle, TriggerId, TriggerInfo, TriggerState};
+pub use supervisor::*;
 
-use crate::{pair_api, ClipboardEvent, ClipboardTrigger, Error, FileSystemTrigger, IntervalTrigger, ProcessEvent, ProcessTrigger, Result, Trigger, TriggerContext, Window, WindowTrigger};
+use crate::{pair_api, Accelerator, ClipboardChange, ClipboardTrigger, Error, FileSystemTrigger, FsEvent, HotkeyTrigger, IntervalTrigger, ProcessEvent, ProcessTrigger, ResourceEvent, ResourceMetric, ResourceTrigger, ScheduleEvent, ScheduleTrigger, Threshold, Trigger, TriggerContext, Window, WindowEvent, WindowEventTrigger, WindowTrigger};
 use derivative::Derivative;
-use notify::Event;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -16,6 +20,10 @@ pub type ErrorHandler = Arc<dyn Fn(Error) + Send + Sync>;
 pub struct Automat {
   triggers: Vec<Box<dyn Trigger>>,
   error_handler: Option<ErrorHandler>,
+  /// Supervision applied to triggers without an override.
+  supervision: Supervision,
+  /// Per-trigger supervision overrides, keyed by index into `triggers`.
+  supervision_overrides: HashMap<usize, Supervision>,
 }
 
 impl Debug for Automat {
@@ -31,6 +39,8 @@ impl Automat {
     Self {
       triggers: Vec::new(),
       error_handler: None,
+      supervision: Supervision::default(),
+      supervision_overrides: HashMap::new(),
     }
   }
 
@@ -42,6 +52,14 @@ impl Automat {
     self
   }
 
+  /// Sets the supervision used for every trigger without its own override.
+  ///
+  /// By default triggers are never restarted and any stopped trigger shuts down the runner.
+  pub fn with_supervision(mut self, supervision: Supervision) -> Self {
+    self.supervision = supervision;
+    self
+  }
+
   pair_api! {
     method
     /// Monitor process starts and exits.
@@ -58,6 +76,14 @@ impl Automat {
       => (ProcessTrigger)::with_interval(f, interval);
   }
 
+  pair_api! {
+    method
+    /// Monitor a system or process resource crossing a threshold.
+    on_resource(metric: ResourceMetric, threshold: Threshold, f: F)
+      callback(TriggerContext<ResourceEvent>)
+      => (ResourceTrigger)::new(metric, threshold, f);
+  }
+
   pair_api! {
     method
     /// Run a callback at regular intervals.
@@ -66,6 +92,26 @@ impl Automat {
       => (IntervalTrigger)::new(interval, f);
   }
 
+  pair_api! {
+    method
+    /// Run a callback according to a cron expression, evaluated in local time.
+    ///
+    /// See [`ScheduleTrigger`] for the expression format and DST handling.
+    on_schedule(expression: &str, f: F)
+      callback(TriggerContext<ScheduleEvent>)
+      => (ScheduleTrigger)::new(expression, f);
+  }
+
+  pair_api! {
+    method
+    /// Run a callback when a global key combination such as `Super+Shift+N` is pressed.
+    ///
+    /// See [`HotkeyTrigger`] for the accelerator syntax and platform support.
+    on_hotkey(accelerator: &str, f: F)
+      callback(TriggerContext<Accelerator>)
+      => (HotkeyTrigger)::new(accelerator, f);
+  }
+
   pair_api! {
     method
     /// Detect when the focused window changes.
@@ -74,18 +120,27 @@ impl Automat {
       => (WindowTrigger)::new(f);
   }
 
+  pair_api! {
+    method
+    /// Detect windows being opened, closed, focused, retitled, moved, resized or
+    /// changing state.
+    on_window_event(f: F)
+      callback(TriggerContext<WindowEvent>)
+      => (WindowEventTrigger)::new(f);
+  }
+
   pair_api! {
     method
     /// Monitor filesystem changes.
     on_fs_watch(f: F)
-      callback(TriggerContext<Result<Event>>)
+      callback(TriggerContext<FsEvent>)
       => (FileSystemTrigger)::new(f);
   }
   
   pair_api! {
     method
     on_clipboard_change(f: F)
-      callback(TriggerContext<ClipboardEvent>)
+      callback(TriggerContext<ClipboardChange>)
       => (ClipboardTrigger)::new(f);
   }
 
@@ -101,6 +156,13 @@ impl Automat {
   }
 
   pub fn extend(mut self, other: Automat) -> Self {
+    let offset = self.triggers.len();
+    self.supervision_overrides.extend(
+      other
+        .supervision_overrides
+        .into_iter()
+        .map(|(index, supervision)| (index + offset, supervision)),
+    );
     self.triggers.extend(other.triggers);
     self
   }
@@ -109,6 +171,19 @@ impl Automat {
     self.triggers.push(Box::new(trigger));
     self
   }
+
+  /// Adds a trigger with its own supervision, overriding the runner-wide default.
+  pub fn with_supervised_trigger<T: Trigger + 'static>(
+    mut self,
+    trigger: T,
+    supervision: Supervision,
+  ) -> Self {
+    self
+      .supervision_overrides
+      .insert(self.triggers.len(), supervision);
+    self.triggers.push(Box::new(trigger));
+    self
+  }
 }
 
 impl Default for Automat {
@@ -1,102 +1,203 @@
-use crate::{await_shutdown, Automat, ErrorHandler, TriggerEvent, TriggerRuntime};
-use tokio::sync::mpsc::{channel, Receiver};
-use tokio::time::{timeout, Duration};
+use crate::automat::handle::{HandleInner, TriggerControl};
+use crate::automat::supervisor::RestartBudget;
+use crate::{
+  Automat, AutomatHandle, Error, Supervision, Trigger, TriggerEvent, TriggerRuntime, TriggerState,
+  await_shutdown,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc::{Receiver, channel};
+use tokio::sync::watch;
+use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
 
+/// How a single run of a trigger ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunOutcome {
+  /// The trigger returned `Ok` or requested a stop.
+  Completed,
+  /// The trigger returned an error or reported a fatal error.
+  Failed,
+}
+
 impl Automat {
+  /// Runs all triggers until Ctrl-C is received or a trigger escalates.
   pub async fn run(self) -> crate::Result<()> {
-    let error_handler = self.error_handler.clone();
-    let mut trigger_handles = Vec::new();
-    let mut event_handles = Vec::new();
+    let handle = self.spawn();
 
-    let shutdown_token = CancellationToken::new();
+    let shutdown_result = tokio::select! {
+      r = await_shutdown() => r,
+      _ = handle.wait() => Ok(()),
+    };
 
-    for mut trigger in self.triggers {
-      let (tx, rx) = channel(100);
-      let handler = error_handler.clone();
+    handle.shutdown().await;
+    shutdown_result
+  }
 
-      let shutdown_for_trigger = shutdown_token.clone();
-      let shutdown_for_events = shutdown_token.clone();
+  /// Starts all triggers in the background and returns a handle to manage them.
+  ///
+  /// Unlike [`run`](Self::run), this does not wait for Ctrl-C; call
+  /// [`AutomatHandle::shutdown`] to stop the runner. Must be called within a Tokio runtime.
+  pub fn spawn(self) -> AutomatHandle {
+    let handle = AutomatHandle::new(self.error_handler, self.supervision);
 
-      let rt = TriggerRuntime {
-        tx: tx.clone(),
-        shutdown: shutdown_for_trigger.clone(),
+    for (index, trigger) in self.triggers.into_iter().enumerate() {
+      match self.supervision_overrides.get(&index) {
+        Some(supervision) => handle.add_supervised(trigger, *supervision),
+        None => handle.add(trigger),
       };
+    }
 
-      let trigger_handle = tokio::spawn(async move {
-        let res = trigger.start(rt).await;
-        if let Err(err) = res {
-          let _ = tx.send(TriggerEvent::ErrorFatal(err)).await;
-        } else {
-          // If a trigger exits cleanly without a shutdown, report it as a stop.
-          if !shutdown_for_trigger.is_cancelled() {
-            let _ = tx.send(TriggerEvent::Stop).await;
-          }
-        }
-      });
+    handle
+  }
+}
 
-      let event_handle =
-        tokio::spawn(
-          async move { Self::handle_trigger_events(rx, handler, shutdown_for_events).await },
-        );
+/// Runs a trigger and restarts it according to its supervision settings.
+///
+/// Each run gets a child of the trigger's cancellation token, so a fatal error, stop
+/// request or pause only stops this trigger. The runner-wide token is cancelled only when
+/// the trigger is given up on and `escalate` is set.
+pub(crate) async fn supervise(
+  mut trigger: Box<dyn Trigger>,
+  supervision: Supervision,
+  inner: Arc<HandleInner>,
+  control: TriggerControl,
+) {
+  let TriggerControl {
+    id,
+    mut paused,
+    cancel,
+  } = control;
+  let (tx, mut rx) = channel(100);
+  let mut budget = RestartBudget::new(supervision);
 
-      trigger_handles.push(trigger_handle);
-      event_handles.push(event_handle);
+  loop {
+    if *paused.borrow() {
+      inner.set_state(id, TriggerState::Paused);
+      tokio::select! {
+        _ = cancel.cancelled() => break,
+        _ = paused.wait_for(|p| !*p) => {}
+      }
     }
 
-    let shutdown_result = tokio::select! {
-      r = await_shutdown() => r,
-      _ = shutdown_token.cancelled() => Ok(()),
+    inner.set_state(id, TriggerState::Running);
+    let run_token = cancel.child_token();
+    let rt = TriggerRuntime {
+      tx: tx.clone(),
+      shutdown: run_token.clone(),
     };
 
-    shutdown_token.cancel();
+    let outcome = run_once(
+      trigger.as_mut(),
+      rt,
+      &mut rx,
+      &inner,
+      &run_token,
+      &mut paused,
+    )
+    .await;
 
-    for trigger_handle in trigger_handles {
-      let mut trigger_handle = trigger_handle;
-      match timeout(Duration::from_secs(2), &mut trigger_handle).await {
-        Ok(join_result) => {
-          let _ = join_result;
-        }
-        Err(_) => {
-          trigger_handle.abort();
-          let _ = trigger_handle.await;
-        }
-      }
+    if trigger.is_running() {
+      let _ = trigger.stop().await;
     }
 
-    for event_handle in event_handles {
-      let _ = event_handle.await;
+    if cancel.is_cancelled() {
+      break;
     }
 
-    shutdown_result
+    // A trigger stopped by a pause is not a failure; wait for it to be resumed instead.
+    if *paused.borrow() && outcome == RunOutcome::Completed {
+      continue;
+    }
+
+    if !supervision
+      .policy
+      .should_restart(outcome == RunOutcome::Failed)
+    {
+      if supervision.escalate {
+        inner.shutdown.cancel();
+      }
+      break;
+    }
+
+    let Some(delay) = budget.next_restart(Instant::now()) else {
+      inner.report(
+        Error::RestartBudgetExhausted(trigger.name(), budget.recent_restarts()),
+        "Trigger error",
+      );
+      if supervision.escalate {
+        inner.shutdown.cancel();
+      }
+      break;
+    };
+
+    inner.set_state(id, TriggerState::Restarting);
+    tokio::select! {
+      _ = cancel.cancelled() => break,
+      _ = sleep(delay) => {}
+    }
   }
 
-  async fn handle_trigger_events(
-    mut rx: Receiver<TriggerEvent>,
-    error_handler: Option<ErrorHandler>,
-    shutdown_token: CancellationToken,
-  ) {
-    while let Some(event) = rx.recv().await {
-      match event {
-        TriggerEvent::Error(err) => {
-          if let Some(ref handler) = error_handler {
-            handler(err);
-          } else {
-            eprintln!("Trigger error: {}", err);
-          }
-        }
-        TriggerEvent::ErrorFatal(err) => {
-          if let Some(ref handler) = error_handler {
-            handler(err);
-          } else {
-            eprintln!("Fatal trigger error: {}", err);
+  let _ = trigger.stop().await;
+  inner.set_state(id, TriggerState::Stopped);
+}
+
+/// Runs the trigger once while handling the events it sends.
+async fn run_once(
+  trigger: &mut dyn Trigger,
+  rt: TriggerRuntime,
+  rx: &mut Receiver<TriggerEvent>,
+  inner: &HandleInner,
+  run_token: &CancellationToken,
+  paused: &mut watch::Receiver<bool>,
+) -> RunOutcome {
+  let mut outcome = RunOutcome::Completed;
+  let start = trigger.start(rt);
+  tokio::pin!(start);
+
+  let result = loop {
+    tokio::select! {
+      res = &mut start => break res,
+      _ = paused.wait_for(|p| *p), if !run_token.is_cancelled() => run_token.cancel(),
+      Some(event) = rx.recv() => {
+        match handle_trigger_event(event, inner) {
+          Some(RunOutcome::Failed) => {
+            outcome = RunOutcome::Failed;
+            run_token.cancel();
           }
-          shutdown_token.cancel();
-        }
-        TriggerEvent::Stop => {
-          shutdown_token.cancel();
+          Some(RunOutcome::Completed) => run_token.cancel(),
+          None => {}
         }
       }
     }
+  };
+
+  // Events sent right before the trigger returned are still queued.
+  while let Ok(event) = rx.try_recv() {
+    if handle_trigger_event(event, inner) == Some(RunOutcome::Failed) {
+      outcome = RunOutcome::Failed;
+    }
+  }
+
+  if let Err(err) = result {
+    inner.report(err, "Fatal trigger error");
+    outcome = RunOutcome::Failed;
+  }
+
+  outcome
+}
+
+/// Reports an event and returns the outcome it forces on the current run, if any.
+fn handle_trigger_event(event: TriggerEvent, inner: &HandleInner) -> Option<RunOutcome> {
+  match event {
+    TriggerEvent::Error(err) => {
+      inner.report(err, "Trigger error");
+      None
+    }
+    TriggerEvent::ErrorFatal(err) => {
+      inner.report(err, "Fatal trigger error");
+      Some(RunOutcome::Failed)
+    }
+    TriggerEvent::Stop => Some(RunOutcome::Completed),
   }
 }
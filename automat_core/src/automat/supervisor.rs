@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Decides whether a trigger is restarted after it stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+  /// Never restart the trigger.
+  #[default]
+  Never,
+  /// Restart only when the trigger failed (returned an error or reported a fatal error).
+  OnFailure,
+  /// Restart whenever the trigger stops, including clean exits.
+  Always,
+}
+
+impl RestartPolicy {
+  /// Returns true if a trigger that stopped with the given outcome should be restarted.
+  pub fn should_restart(&self, failed: bool) -> bool {
+    match self {
+      RestartPolicy::Never => false,
+      RestartPolicy::OnFailure => failed,
+      RestartPolicy::Always => true,
+    }
+  }
+}
+
+/// Exponential backoff applied between restarts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+  pub initial: Duration,
+  pub max: Duration,
+  pub multiplier: f64,
+}
+
+impl Backoff {
+  /// Creates a backoff doubling from `initial` up to `max`.
+  pub fn new(initial: Duration, max: Duration) -> Self {
+    Self {
+      initial,
+      max,
+      multiplier: 2.0,
+    }
+  }
+
+  /// Sets the growth factor applied after each restart.
+  pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+    self.multiplier = multiplier;
+    self
+  }
+
+  /// Returns the delay before the restart with the given zero-based index.
+  pub fn delay(&self, attempt: u32) -> Duration {
+    let factor = self
+      .multiplier
+      .max(1.0)
+      .powi(attempt.min(i32::MAX as u32) as i32);
+    let secs = self.initial.as_secs_f64() * factor;
+
+    if !secs.is_finite() || secs >= self.max.as_secs_f64() {
+      self.max
+    } else {
+      Duration::from_secs_f64(secs)
+    }
+  }
+}
+
+impl Default for Backoff {
+  fn default() -> Self {
+    Self::new(Duration::from_millis(500), Duration::from_secs(30))
+  }
+}
+
+/// Supervision settings for a trigger managed by `Automat::run`.
+///
+/// The default never restarts and escalates, so a trigger that stops shuts down the
+/// whole runner.
+///
+/// ```no_run
+/// use automat_core::{RestartPolicy, Supervision};
+/// use std::time::Duration;
+///
+/// // Restart on failure, at most 5 times per minute, then leave the other triggers running.
+/// let supervision = Supervision::new(RestartPolicy::OnFailure)
+///   .with_max_restarts(5, Duration::from_secs(60))
+///   .escalate(false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Supervision {
+  pub policy: RestartPolicy,
+  pub backoff: Backoff,
+  /// Maximum number of restarts allowed within `window`.
+  pub max_restarts: u32,
+  pub window: Duration,
+  /// Whether giving up on this trigger shuts down every other trigger too.
+  pub escalate: bool,
+}
+
+impl Supervision {
+  /// Creates supervision settings with the given restart policy.
+  pub fn new(policy: RestartPolicy) -> Self {
+    Self {
+      policy,
+      backoff: Backoff::default(),
+      max_restarts: 3,
+      window: Duration::from_secs(60),
+      escalate: true,
+    }
+  }
+
+  /// Sets the backoff applied between restarts.
+  pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+    self.backoff = backoff;
+    self
+  }
+
+  /// Allows at most `max_restarts` restarts within `window` before giving up.
+  pub fn with_max_restarts(mut self, max_restarts: u32, window: Duration) -> Self {
+    self.max_restarts = max_restarts;
+    self.window = window;
+    self
+  }
+
+  /// Sets whether giving up on this trigger shuts down the whole runner.
+  pub fn escalate(mut self, escalate: bool) -> Self {
+    self.escalate = escalate;
+    self
+  }
+}
+
+impl Default for Supervision {
+  fn default() -> Self {
+    Self::new(RestartPolicy::Never)
+  }
+}
+
+/// Tracks restarts of a single trigger against its restart budget.
+#[derive(Debug)]
+pub(crate) struct RestartBudget {
+  supervision: Supervision,
+  restarts: VecDeque<Instant>,
+}
+
+impl RestartBudget {
+  pub(crate) fn new(supervision: Supervision) -> Self {
+    Self {
+      supervision,
+      restarts: VecDeque::new(),
+    }
+  }
+
+  /// Records a restart and returns the delay to wait before it,
+  /// or `None` if the budget is exhausted.
+  pub(crate) fn next_restart(&mut self, now: Instant) -> Option<Duration> {
+    while let Some(&oldest) = self.restarts.front() {
+      if now.duration_since(oldest) > self.supervision.window {
+        self.restarts.pop_front();
+      } else {
+        break;
+      }
+    }
+
+    if self.restarts.len() >= self.supervision.max_restarts as usize {
+      return None;
+    }
+
+    let delay = self.supervision.backoff.delay(self.restarts.len() as u32);
+    self.restarts.push_back(now);
+    Some(delay)
+  }
+
+  /// Number of restarts within the current window.
+  pub(crate) fn recent_restarts(&self) -> u32 {
+    self.restarts.len() as u32
+  }
+}
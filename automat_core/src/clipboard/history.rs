@@ -0,0 +1,376 @@
+//! Clipboard history, persisted to a file.
+
+use crate::{ClipboardEvent, ClipboardTrigger, Error, Result, Window, set_clipboard_text};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Password managers whose copies are kept out of the history by default, matched
+/// against the executable path of the focused window.
+pub const DEFAULT_SENSITIVE_EXECUTABLES: &[&str] = &[
+  "1password",
+  "bitwarden",
+  "dashlane",
+  "enpass",
+  "keepass",
+  "lastpass",
+  "proton pass",
+];
+
+/// Decides whether a copy is sensitive and must not be recorded.
+pub type SensitiveRule = Arc<dyn Fn(&ClipboardEvent, Option<&Window>) -> bool + Send + Sync>;
+
+/// A text copied to the clipboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+  /// Identifies the entry in [`ClipboardHistory`] methods. Never reused.
+  pub id: u64,
+  pub text: String,
+  /// When the text was last copied.
+  pub copied_at: SystemTime,
+  /// Pinned entries are never evicted.
+  pub pinned: bool,
+  /// Executable of the window that was focused when the text was copied.
+  pub source: Option<String>,
+}
+
+/// The entries as stored on disk.
+#[derive(Default, Serialize, Deserialize)]
+struct Stored {
+  next_id: u64,
+  /// Newest first.
+  entries: VecDeque<HistoryEntry>,
+}
+
+/// An opt-in history of copied texts.
+///
+/// The history holds up to a fixed number of entries, evicting the oldest unpinned
+/// one when full, and is saved to a JSON file after every change. Copying a text that
+/// is already in the history moves its entry to the front. Images and files are not
+/// recorded.
+///
+/// It is a cheap handle: clones share the same history, so one can record copies
+/// through [`trigger`](Self::trigger) while another lists or restores entries.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///   let history = ClipboardHistory::open("clipboard-history.json")?
+///     .with_capacity(200)
+///     .with_max_item_size(16 * 1024);
+///
+///   for entry in history.search("https://") {
+///     println!("{}: {}", entry.id, entry.text);
+///   }
+///
+///   Automat::new().with_trigger(history.trigger()).run().await
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ClipboardHistory {
+  inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+  path: PathBuf,
+  capacity: usize,
+  max_item_size: usize,
+  sensitive_executables: Vec<String>,
+  sensitive_rules: Vec<SensitiveRule>,
+  stored: Stored,
+}
+
+impl ClipboardHistory {
+  /// Loads the history saved at `path`, or starts an empty one if the file does not
+  /// exist yet.
+  ///
+  /// Keeps 100 entries of up to 64 KiB by default, and excludes copies made while a
+  /// [password manager](DEFAULT_SENSITIVE_EXECUTABLES) is focused.
+  pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+    let path = path.into();
+    let stored = match std::fs::read(&path) {
+      Ok(bytes) => serde_json::from_slice(&bytes)
+        .map_err(|e| Error::InvalidHistory(format!("{}: {e}", path.display())))?,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => Stored::default(),
+      Err(e) => return Err(e.into()),
+    };
+
+    Ok(Self {
+      inner: Arc::new(Mutex::new(Inner {
+        path,
+        capacity: 100,
+        max_item_size: 64 * 1024,
+        sensitive_executables: DEFAULT_SENSITIVE_EXECUTABLES
+          .iter()
+          .map(ToString::to_string)
+          .collect(),
+        sensitive_rules: Vec::new(),
+        stored,
+      })),
+    })
+  }
+
+  /// Sets how many unpinned entries are kept. Older ones are evicted on the next copy.
+  pub fn with_capacity(self, capacity: usize) -> Self {
+    self.inner.lock().capacity = capacity;
+    self
+  }
+
+  /// Sets the size in bytes above which texts are not recorded.
+  pub fn with_max_item_size(self, max_item_size: usize) -> Self {
+    self.inner.lock().max_item_size = max_item_size;
+    self
+  }
+
+  /// Replaces the executables whose copies are not recorded.
+  ///
+  /// A copy is excluded when the executable path of the focused window contains one of
+  /// them, ignoring case.
+  pub fn with_sensitive_executables<I, S>(self, executables: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.inner.lock().sensitive_executables = executables
+      .into_iter()
+      .map(|executable| executable.into().to_lowercase())
+      .collect();
+    self
+  }
+
+  /// Adds a rule excluding copies it returns `true` for, given the copied content and
+  /// the focused window.
+  pub fn with_sensitive_rule<F>(self, rule: F) -> Self
+  where
+    F: Fn(&ClipboardEvent, Option<&Window>) -> bool + Send + Sync + 'static,
+  {
+    self.inner.lock().sensitive_rules.push(Arc::new(rule));
+    self
+  }
+
+  /// Returns a trigger recording every clipboard change into this history.
+  ///
+  /// The primary selection is not recorded, as it changes with every selected text.
+  pub fn trigger(&self) -> ClipboardTrigger {
+    let history = self.clone();
+    ClipboardTrigger::new_blocking(move |ctx| {
+      history.record(&ctx.data.content, Window::current().as_ref())?;
+      Ok(())
+    })
+  }
+
+  /// Records a copy made while `source` was focused.
+  ///
+  /// Returns whether it was recorded, which it is not if it has no text, is too large
+  /// or is sensitive.
+  pub fn record(&self, content: &ClipboardEvent, source: Option<&Window>) -> Result<bool> {
+    let Some(text) = content.text().filter(|text| !text.trim().is_empty()) else {
+      return Ok(false);
+    };
+
+    let executable = source.and_then(Window::executable_path);
+    let mut inner = self.inner.lock();
+    if text.len() > inner.max_item_size
+      || inner.is_sensitive(content, source, executable.as_deref())
+    {
+      return Ok(false);
+    }
+
+    let stored = &mut inner.stored;
+    let existing = stored
+      .entries
+      .iter()
+      .position(|entry| entry.text == text)
+      .and_then(|index| stored.entries.remove(index));
+    let (id, pinned) = match existing {
+      Some(entry) => (entry.id, entry.pinned),
+      None => {
+        stored.next_id += 1;
+        (stored.next_id - 1, false)
+      }
+    };
+
+    stored.entries.push_front(HistoryEntry {
+      id,
+      text: text.to_string(),
+      copied_at: SystemTime::now(),
+      pinned,
+      source: executable,
+    });
+
+    inner.evict();
+    inner.save()?;
+    Ok(true)
+  }
+
+  /// Returns all entries, newest first.
+  pub fn entries(&self) -> Vec<HistoryEntry> {
+    self.inner.lock().stored.entries.iter().cloned().collect()
+  }
+
+  /// Returns the entries containing `query`, ignoring case, newest first.
+  pub fn search(&self, query: &str) -> Vec<HistoryEntry> {
+    let query = query.to_lowercase();
+    self
+      .inner
+      .lock()
+      .stored
+      .entries
+      .iter()
+      .filter(|entry| entry.text.to_lowercase().contains(&query))
+      .cloned()
+      .collect()
+  }
+
+  /// Returns the entry with the given id.
+  pub fn get(&self, id: u64) -> Option<HistoryEntry> {
+    let inner = self.inner.lock();
+    inner
+      .stored
+      .entries
+      .iter()
+      .find(|entry| entry.id == id)
+      .cloned()
+  }
+
+  /// Pins or unpins an entry. Pinned entries are never evicted.
+  pub fn pin(&self, id: u64, pinned: bool) -> Result<()> {
+    let mut inner = self.inner.lock();
+    inner.entry_mut(id)?.pinned = pinned;
+    inner.evict();
+    inner.save()
+  }
+
+  /// Removes an entry, pinned or not.
+  pub fn delete(&self, id: u64) -> Result<()> {
+    let mut inner = self.inner.lock();
+    let index = inner.index(id)?;
+    inner.stored.entries.remove(index);
+    inner.save()
+  }
+
+  /// Removes all unpinned entries.
+  pub fn clear(&self) -> Result<()> {
+    let mut inner = self.inner.lock();
+    inner.stored.entries.retain(|entry| entry.pinned);
+    inner.save()
+  }
+
+  /// Puts an entry back on the clipboard.
+  ///
+  /// With the history's [trigger](Self::trigger) running, this moves the entry to the
+  /// front like any other copy.
+  pub fn copy(&self, id: u64) -> Result<()> {
+    let text = self.get(id).ok_or(Error::HistoryEntryNotFound(id))?.text;
+    set_clipboard_text(&text)
+  }
+
+  /// The file the history is saved to.
+  pub fn path(&self) -> PathBuf {
+    self.inner.lock().path.clone()
+  }
+}
+
+impl Inner {
+  fn is_sensitive(
+    &self,
+    content: &ClipboardEvent,
+    source: Option<&Window>,
+    executable: Option<&str>,
+  ) -> bool {
+    let executable = executable.map(str::to_lowercase);
+    let by_executable = executable.is_some_and(|executable| {
+      self
+        .sensitive_executables
+        .iter()
+        .any(|sensitive| executable.contains(sensitive.as_str()))
+    });
+
+    by_executable
+      || self
+        .sensitive_rules
+        .iter()
+        .any(|rule| rule(content, source))
+  }
+
+  fn index(&self, id: u64) -> Result<usize> {
+    self
+      .stored
+      .entries
+      .iter()
+      .position(|entry| entry.id == id)
+      .ok_or(Error::HistoryEntryNotFound(id))
+  }
+
+  fn entry_mut(&mut self, id: u64) -> Result<&mut HistoryEntry> {
+    let index = self.index(id)?;
+    Ok(&mut self.stored.entries[index])
+  }
+
+  /// Drops the oldest unpinned entries beyond the capacity.
+  fn evict(&mut self) {
+    let mut unpinned = 0;
+    let capacity = self.capacity;
+    self.stored.entries.retain(|entry| {
+      if entry.pinned {
+        return true;
+      }
+      unpinned += 1;
+      unpinned <= capacity
+    });
+  }
+
+  /// Writes the history to a temporary file and moves it into place, so a crash never
+  /// leaves a truncated file behind.
+  fn save(&self) -> Result<()> {
+    if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+      std::fs::create_dir_all(dir)?;
+    }
+
+    let json =
+      serde_json::to_vec_pretty(&self.stored).map_err(|e| Error::InvalidHistory(e.to_string()))?;
+    let tmp = temporary_path(&self.path);
+    write_private(&tmp, &json)?;
+    std::fs::rename(&tmp, &self.path)?;
+    Ok(())
+  }
+}
+
+/// Writes a file only its owner can read, as the history holds whatever was copied.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+  use std::io::Write;
+
+  let mut options = std::fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+  let mut file = options.open(path)?;
+  // The mode only applies to new files, so a leftover temporary file is fixed too.
+  #[cfg(unix)]
+  file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+  file.write_all(contents)
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+  let mut name = path.file_name().unwrap_or_default().to_os_string();
+  name.push(".tmp");
+  path.with_file_name(name)
+}
+
+impl Debug for ClipboardHistory {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let inner = self.inner.lock();
+    f.debug_struct("ClipboardHistory")
+      .field("path", &inner.path)
+      .field("entries", &inner.stored.entries.len())
+      .field("capacity", &inner.capacity)
+      .finish_non_exhaustive()
+  }
+}
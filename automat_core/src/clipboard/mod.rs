@@ -0,0 +1,216 @@
+//! Clipboard management using 1Password's arboard library.
+
+mod history;
+
+pub use history::*;
+
+use crate::{ClipboardEvent, ClipboardSelection, Error, Result};
+use arboard::{Clipboard, ImageData};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Global clipboard instance, lazily initialized on first access.
+static CLIPBOARD: Lazy<Mutex<Clipboard>> = Lazy::new(|| Mutex::new(Clipboard::new().unwrap()));
+
+/// Gets the current text content from the system clipboard.
+///
+/// Returns an error if the clipboard is empty, contains non-text data, or can't be accessed
+/// due to system restrictions.
+///
+/// ```no_run
+/// let text = get_clipboard_text()?;
+/// println!("Clipboard contains: {}", text);
+/// ```
+#[inline(always)]
+pub fn get_clipboard_text() -> Result<String> {
+  CLIPBOARD.lock().get_text().map_err(Error::ClipboardError)
+}
+
+/// Sets the system clipboard to the specified text content.
+///
+/// This replaces any existing clipboard content with the provided text.
+/// Returns an error if the clipboard can't be accessed or modified due to system restrictions
+/// or permission issues.
+///
+/// ```no_run
+/// set_clipboard_text("Hello, clipboard!")?;
+/// ```
+#[inline(always)]
+pub fn set_clipboard_text(text: &str) -> Result<()> {
+  CLIPBOARD
+    .lock()
+    .set_text(text)
+    .map_err(Error::ClipboardError)
+}
+
+/// Gets the current HTML content from the system clipboard, as copied from a browser or
+/// rich text editor.
+///
+/// Returns an error if the clipboard holds no HTML.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let html = get_clipboard_html()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn get_clipboard_html() -> Result<String> {
+  CLIPBOARD.lock().get().html().map_err(Error::ClipboardError)
+}
+
+/// Gets the paths of the files on the system clipboard, as copied from a file manager.
+///
+/// Returns an error if the clipboard holds no files.
+pub fn get_clipboard_files() -> Result<Vec<PathBuf>> {
+  CLIPBOARD
+    .lock()
+    .get()
+    .file_list()
+    .map_err(Error::ClipboardError)
+}
+
+/// Gets the clipboard content in the richest format it is available in.
+///
+/// Files are preferred over images, images over HTML and HTML over plain text, since
+/// applications offer the richer formats along with a plain text fallback. Returns
+/// `None` if the clipboard is empty or only holds formats that cannot be read.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// match get_clipboard()? {
+///   Some(ClipboardEvent::Image { width, height, .. }) => println!("{width}x{height} image"),
+///   Some(ClipboardEvent::Text(text)) => println!("{text}"),
+///   _ => {}
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn get_clipboard() -> Result<Option<ClipboardEvent>> {
+  get_clipboard_selection(ClipboardSelection::Clipboard)
+}
+
+/// Gets the content of a selection in the richest format it is available in, like
+/// [`get_clipboard`].
+///
+/// The primary selection always reads as empty on platforms without one.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// if let Some(selected) = get_clipboard_selection(ClipboardSelection::Primary)? {
+///   println!("{:?}", selected.text());
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn get_clipboard_selection(selection: ClipboardSelection) -> Result<Option<ClipboardEvent>> {
+  if cfg!(not(target_os = "linux")) && selection == ClipboardSelection::Primary {
+    return Ok(None);
+  }
+  let mut clipboard = CLIPBOARD.lock();
+
+  if let Some(files) = available(read(&mut clipboard, selection).file_list())?
+    && !files.is_empty()
+  {
+    return Ok(Some(ClipboardEvent::Files(files)));
+  }
+
+  if let Some(image) = available(read(&mut clipboard, selection).image())? {
+    return Ok(Some(ClipboardEvent::Image {
+      width: image.width,
+      height: image.height,
+      rgba: image.bytes.into_owned(),
+    }));
+  }
+
+  if let Some(html) = available(read(&mut clipboard, selection).html())? {
+    let text = available(read(&mut clipboard, selection).text())?;
+    return Ok(Some(ClipboardEvent::Html { html, text }));
+  }
+
+  Ok(available(read(&mut clipboard, selection).text())?.map(ClipboardEvent::Text))
+}
+
+/// Starts a read of `selection`.
+#[cfg(target_os = "linux")]
+fn read(clipboard: &mut Clipboard, selection: ClipboardSelection) -> arboard::Get<'_> {
+  use arboard::{GetExtLinux, LinuxClipboardKind};
+
+  clipboard.get().clipboard(match selection {
+    ClipboardSelection::Clipboard => LinuxClipboardKind::Clipboard,
+    ClipboardSelection::Primary => LinuxClipboardKind::Primary,
+  })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read(clipboard: &mut Clipboard, _selection: ClipboardSelection) -> arboard::Get<'_> {
+  clipboard.get()
+}
+
+/// Turns "not available in this format" into `None`.
+fn available<T>(result: std::result::Result<T, arboard::Error>) -> Result<Option<T>> {
+  match result {
+    Ok(value) => Ok(Some(value)),
+    Err(arboard::Error::ContentNotAvailable | arboard::Error::ConversionFailure) => Ok(None),
+    Err(err) => Err(Error::ClipboardError(err)),
+  }
+}
+
+/// Sets the system clipboard to HTML, with a plain text version for applications that
+/// cannot paste HTML.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// set_clipboard_html("<b>Hello</b>, clipboard!", Some("Hello, clipboard!"))?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn set_clipboard_html(html: &str, alt_text: Option<&str>) -> Result<()> {
+  CLIPBOARD
+    .lock()
+    .set_html(html, alt_text)
+    .map_err(Error::ClipboardError)
+}
+
+/// Sets the system clipboard to an image of `width` by `height` pixels, given as RGBA
+/// bytes in rows from the top.
+///
+/// Returns an error if `rgba` does not hold `width * height * 4` bytes.
+pub fn set_clipboard_image(width: usize, height: usize, rgba: &[u8]) -> Result<()> {
+  if width.checked_mul(height).and_then(|n| n.checked_mul(4)) != Some(rgba.len()) {
+    return Err(Error::ClipboardError(arboard::Error::ConversionFailure));
+  }
+
+  CLIPBOARD
+    .lock()
+    .set_image(ImageData {
+      width,
+      height,
+      bytes: Cow::Borrowed(rgba),
+    })
+    .map_err(Error::ClipboardError)
+}
+
+/// Puts files on the system clipboard, so they can be pasted in a file manager.
+pub fn set_clipboard_files<P: AsRef<Path>>(paths: &[P]) -> Result<()> {
+  CLIPBOARD
+    .lock()
+    .set()
+    .file_list(paths)
+    .map_err(Error::ClipboardError)
+}
+
+/// Sets the system clipboard to the given content.
+pub fn set_clipboard(content: &ClipboardEvent) -> Result<()> {
+  match content {
+    ClipboardEvent::Text(text) => set_clipboard_text(text),
+    ClipboardEvent::Html { html, text } => set_clipboard_html(html, text.as_deref()),
+    ClipboardEvent::Image {
+      width,
+      height,
+      rgba,
+    } => set_clipboard_image(*width, *height, rgba),
+    ClipboardEvent::Files(paths) => set_clipboard_files(paths),
+  }
+}
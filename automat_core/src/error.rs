@@ -19,6 +19,9 @@ pub enum Error {
   #[error("Window title error: {0}")]
   WindowTitleError(String),
 
+  #[error("Window geometry error: {0}")]
+  WindowGeometryError(String),
+
   #[error("Clipboard error: {0}")]
   ClipboardError(#[from] arboard::Error),
 
@@ -39,6 +42,54 @@ pub enum Error {
 
   #[error("Failed to send trigger event (channel full or closed)")]
   ChannelSend,
+
+  #[error("{0} stopped after exhausting its restart budget ({1} restarts)")]
+  RestartBudgetExhausted(String, u32),
+
+  #[error("Invalid schedule {0}")]
+  InvalidSchedule(String),
+
+  #[error("No trigger with id {0}")]
+  TriggerNotFound(crate::TriggerId),
+
+  #[error("DISPLAY is not set; no X server to connect to")]
+  DisplayNotSet,
+
+  #[error("Cannot open X display {0}")]
+  DisplayUnavailable(String),
+
+  #[error("Invalid accelerator {0}")]
+  InvalidAccelerator(String),
+
+  #[error("Hotkey {0} is already grabbed by another application")]
+  HotkeyConflict(String),
+
+  #[error("Hotkey error: {0}")]
+  HotkeyError(String),
+
+  #[error("Step {step} failed: {source}")]
+  StepFailed { step: String, source: Box<Error> },
+
+  #[error("Action timed out after {0:?}")]
+  ActionTimedOut(std::time::Duration),
+
+  #[error("Command failed: {0}")]
+  CommandFailed(String),
+
+  #[error("Invalid glob pattern {0}")]
+  InvalidGlob(String),
+
+  #[error("Invalid regular expression {0}")]
+  InvalidRegex(String),
+
+  #[error("No disk found for {}", .0.display())]
+  DiskNotFound(std::path::PathBuf),
+
+  #[error("No clipboard history entry with id {0}")]
+  HistoryEntryNotFound(u64),
+
+  #[error("Invalid clipboard history: {0}")]
+  InvalidHistory(String),
 }
 
 impl From<DynError> for Error {
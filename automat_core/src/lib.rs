@@ -18,8 +18,11 @@ mod callback;
 mod clipboard;
 mod display_macro;
 mod error;
+#[cfg(target_os = "linux")]
+mod linux;
 mod macros;
 mod main_loop;
+mod monitor;
 mod triggers;
 mod window;
 
@@ -28,8 +31,10 @@ pub use automat::*;
 pub use clipboard::*;
 pub use error::*;
 pub use main_loop::*;
+pub use monitor::*;
 pub use triggers::*;
 pub use window::*;
 
 pub use async_trait::async_trait;
+pub use chrono_tz::Tz;
 pub use display_info::{error::*, DisplayInfo};
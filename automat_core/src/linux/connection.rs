@@ -0,0 +1,248 @@
+use crate::linux::{NET_SUPPORTED, long_property};
+use crate::{Error, Result};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_long, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicU8, Ordering};
+use std::sync::{Once, OnceLock};
+use x11::xlib::{
+  Atom, ClientMessage, Display, SubstructureNotifyMask, SubstructureRedirectMask, XA_ATOM,
+  XClientMessageEvent, XCloseDisplay, XConnectionNumber, XDefaultRootWindow, XErrorEvent, XEvent,
+  XFlush, XInternAtom, XOpenDisplay, XSendEvent, XSetErrorHandler, XSetIOErrorHandler, XSync,
+};
+
+/// `XSetIOErrorExitHandler`, which sets what Xlib calls instead of exiting after an I/O
+/// error on a display. Added in libX11 1.7 and not bound by the `x11` crate.
+type SetIOErrorExitHandler = unsafe extern "C" fn(
+  display: *mut Display,
+  handler: Option<unsafe extern "C" fn(*mut Display, *mut c_void)>,
+  user_data: *mut c_void,
+);
+
+/// Looks up `XSetIOErrorExitHandler` at runtime, so older libX11 versions still load.
+///
+/// Returns `None` on those, where an I/O error still exits the process.
+fn set_io_error_exit_handler() -> Option<SetIOErrorExitHandler> {
+  static FUNCTION: OnceLock<Option<SetIOErrorExitHandler>> = OnceLock::new();
+  *FUNCTION.get_or_init(|| {
+    // SAFETY: the name is a valid C string, and libX11 is linked into the process.
+    let symbol = unsafe { libc::dlsym(libc::RTLD_DEFAULT, c"XSetIOErrorExitHandler".as_ptr()) };
+    // SAFETY: the symbol, when present, is the libX11 function with this signature.
+    (!symbol.is_null())
+      .then(|| unsafe { std::mem::transmute::<*mut c_void, SetIOErrorExitHandler>(symbol) })
+  })
+}
+
+/// The process-wide connection, opened on first use.
+static CONNECTION: Mutex<Option<XConnection>> = Mutex::new(None);
+
+/// Runs `f` with the shared X connection, opening or reopening it as needed.
+///
+/// Calls are serialized, so the connection can be used from any thread or tokio task.
+/// Keep `f` short: it holds the connection for every other caller.
+pub(crate) fn with_connection<R>(f: impl FnOnce(&mut XConnection) -> R) -> Result<R> {
+  let mut guard = CONNECTION.lock();
+
+  // A connection whose server went away is closed and a new one is opened, so the
+  // window functions keep working after the X server restarts.
+  let conn = match guard.take().filter(|conn| is_alive(conn.display)) {
+    Some(conn) => conn,
+    None => XConnection::open()?,
+  };
+
+  Ok(f(guard.insert(conn)))
+}
+
+/// A connection to the X server with a cache of interned atoms.
+pub(crate) struct XConnection {
+  display: *mut Display,
+  root: x11::xlib::Window,
+  atoms: HashMap<&'static CStr, Atom>,
+}
+
+// SAFETY: the shared connection is only reachable through `CONNECTION`, whose lock
+// serializes every Xlib call made on it.
+unsafe impl Send for XConnection {}
+
+impl XConnection {
+  fn open() -> Result<Self> {
+    let display = open_display()?;
+    Ok(Self {
+      display,
+      root: unsafe { XDefaultRootWindow(display) },
+      atoms: HashMap::new(),
+    })
+  }
+
+  pub(crate) fn display(&self) -> *mut Display {
+    self.display
+  }
+
+  pub(crate) fn root(&self) -> x11::xlib::Window {
+    self.root
+  }
+
+  /// Returns the atom with the given name, interning it on first use.
+  pub(crate) fn atom(&mut self, name: &'static CStr) -> Atom {
+    let display = self.display;
+    *self
+      .atoms
+      .entry(name)
+      .or_insert_with(|| unsafe { XInternAtom(display, name.as_ptr(), 0) })
+  }
+
+  /// Returns true if the window manager lists the hint in `_NET_SUPPORTED`.
+  pub(crate) fn supports(&mut self, hint: &'static CStr) -> bool {
+    let supported = self.atom(NET_SUPPORTED);
+    let hint = self.atom(hint);
+    long_property(self.display, self.root, supported, XA_ATOM)
+      .is_some_and(|hints| hints.contains(&hint))
+  }
+
+  /// Sends an EWMH client message about `window` to the root window, where the window
+  /// manager picks it up.
+  pub(crate) fn send_wm_message(
+    &mut self,
+    window: x11::xlib::Window,
+    message_type: &'static CStr,
+    data: [c_long; 5],
+  ) {
+    let message_type = self.atom(message_type);
+
+    unsafe {
+      let mut event: XClientMessageEvent = std::mem::zeroed();
+      event.type_ = ClientMessage;
+      event.window = window;
+      event.message_type = message_type;
+      event.format = 32;
+      for (i, value) in data.into_iter().enumerate() {
+        event.data.set_long(i, value);
+      }
+
+      XSendEvent(
+        self.display,
+        self.root,
+        0,
+        SubstructureNotifyMask | SubstructureRedirectMask,
+        (&raw mut event).cast::<XEvent>(),
+      );
+      XFlush(self.display);
+    }
+  }
+}
+
+impl Drop for XConnection {
+  fn drop(&mut self) {
+    close_display(self.display);
+  }
+}
+
+/// Opens a new display connection using `DISPLAY`.
+///
+/// With libX11 1.7 or later, an I/O error on the connection, such as the server going
+/// away, does not exit the process; Xlib calls on it fail from then on until it is
+/// closed. Older versions exit as they always did.
+pub(crate) fn open_display() -> Result<*mut Display> {
+  let name = std::env::var("DISPLAY").unwrap_or_default();
+  if name.is_empty() {
+    return Err(Error::DisplayNotSet);
+  }
+
+  install_error_handler();
+
+  let display = unsafe { XOpenDisplay(ptr::null()) };
+  if display.is_null() {
+    return Err(Error::DisplayUnavailable(name));
+  }
+
+  if let Some(set_handler) = set_io_error_exit_handler() {
+    unsafe { set_handler(display, Some(ignore_io_error_exit), ptr::null_mut()) };
+  }
+  Ok(display)
+}
+
+/// Closes a display opened with [`open_display`].
+///
+/// Closing a connection whose server went away hits an I/O error, so without an exit
+/// handler to catch it the display is leaked instead of exiting the process.
+pub(crate) fn close_display(display: *mut Display) {
+  if set_io_error_exit_handler().is_some() || is_alive(display) {
+    unsafe { XCloseDisplay(display) };
+  }
+}
+
+/// Returns false once the server closed the connection.
+///
+/// Peeks at the socket without consuming anything, so a dead connection is noticed
+/// before Xlib reports an I/O error on it.
+pub(crate) fn is_alive(display: *mut Display) -> bool {
+  let fd = unsafe { XConnectionNumber(display) };
+  let mut byte = 0u8;
+  let read = unsafe {
+    libc::recv(
+      fd,
+      (&raw mut byte).cast(),
+      1,
+      libc::MSG_PEEK | libc::MSG_DONTWAIT,
+    )
+  };
+
+  match read {
+    0 => false,
+    n if n > 0 => true,
+    _ => std::io::Error::last_os_error().kind() == std::io::ErrorKind::WouldBlock,
+  }
+}
+
+static TRAP_LOCK: Mutex<()> = Mutex::new(());
+static TRAP_DISPLAY: AtomicPtr<Display> = AtomicPtr::new(ptr::null_mut());
+static TRAPPED_ERROR: AtomicU8 = AtomicU8::new(0);
+
+/// Runs `f` on `display` and returns the X error code it caused, if any.
+///
+/// Errors are reported asynchronously, so the connection is synced before returning to
+/// collect every error caused by `f`.
+pub(crate) fn trap_errors<R>(display: *mut Display, f: impl FnOnce() -> R) -> (R, Option<u8>) {
+  let _guard = TRAP_LOCK.lock();
+
+  TRAPPED_ERROR.store(0, Ordering::SeqCst);
+  TRAP_DISPLAY.store(display, Ordering::SeqCst);
+
+  let result = f();
+  unsafe { XSync(display, 0) };
+
+  TRAP_DISPLAY.store(ptr::null_mut(), Ordering::SeqCst);
+  let code = TRAPPED_ERROR.swap(0, Ordering::SeqCst);
+  (result, (code != 0).then_some(code))
+}
+
+/// Replaces Xlib's default error handlers, which exit the process.
+///
+/// Errors such as `BadWindow` are expected when querying a window that just closed, so they
+/// are ignored unless they happen inside [`trap_errors`]. I/O errors are left to the
+/// per-display exit handler set in [`open_display`].
+fn install_error_handler() {
+  static INSTALL: Once = Once::new();
+  INSTALL.call_once(|| unsafe {
+    XSetErrorHandler(Some(handle_error));
+    XSetIOErrorHandler(Some(handle_io_error));
+  });
+}
+
+unsafe extern "C" fn handle_io_error(_: *mut Display) -> c_int {
+  0
+}
+
+/// Returning instead of exiting leaves the display marked as broken.
+unsafe extern "C" fn ignore_io_error_exit(_: *mut Display, _: *mut c_void) {}
+
+unsafe extern "C" fn handle_error(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+  if display == TRAP_DISPLAY.load(Ordering::SeqCst) {
+    let code = unsafe { (*event).error_code };
+    // Keep the first error; later ones are usually consequences of it.
+    let _ = TRAPPED_ERROR.compare_exchange(0, code, Ordering::SeqCst, Ordering::SeqCst);
+  }
+  0
+}
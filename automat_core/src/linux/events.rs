@@ -0,0 +1,105 @@
+use crate::linux::{close_display, is_alive, open_display, trap_errors};
+use crate::{Error, Result};
+use std::mem::MaybeUninit;
+use std::os::fd::{AsRawFd, RawFd};
+use tokio::io::unix::AsyncFd;
+use x11::xlib::{
+  Display, XCloseDisplay, XConnectionNumber, XDefaultRootWindow, XEvent, XNextEvent, XPending,
+};
+
+/// What [`XEventConnection::next_event`] fails with once the server went away.
+const CONNECTION_CLOSED: &str = "connection closed";
+
+/// Raw file descriptor of an X connection, registered with the Tokio reactor.
+struct DisplayFd(RawFd);
+
+impl AsRawFd for DisplayFd {
+  fn as_raw_fd(&self) -> RawFd {
+    self.0
+  }
+}
+
+/// A dedicated X connection whose events can be awaited without blocking the runtime.
+///
+/// Used by triggers that select input on the X server (key grabs, property changes).
+/// Each instance owns its display, so events for one trigger never reach another, and
+/// waiting for events never holds up the shared connection used by the window functions.
+pub(crate) struct XEventConnection {
+  display: *mut Display,
+  /// Always `Some` until dropped; taken first so the fd is deregistered before it closes.
+  fd: Option<AsyncFd<DisplayFd>>,
+}
+
+// SAFETY: the display is owned by this connection and only used through `&self`/`&mut self`
+// by one task at a time; Xlib calls never happen concurrently on it.
+unsafe impl Send for XEventConnection {}
+// SAFETY: see above. Triggers keep the connection inside a single task.
+unsafe impl Sync for XEventConnection {}
+
+impl XEventConnection {
+  /// Opens a new connection to the display named by `DISPLAY`.
+  pub(crate) fn open() -> Result<Self> {
+    let display = open_display()?;
+    let fd = unsafe { XConnectionNumber(display) };
+    match AsyncFd::new(DisplayFd(fd)) {
+      Ok(fd) => Ok(Self {
+        display,
+        fd: Some(fd),
+      }),
+      Err(err) => {
+        unsafe { XCloseDisplay(display) };
+        Err(err.into())
+      }
+    }
+  }
+
+  pub(crate) fn display(&self) -> *mut Display {
+    self.display
+  }
+
+  pub(crate) fn root(&self) -> x11::xlib::Window {
+    unsafe { XDefaultRootWindow(self.display) }
+  }
+
+  /// Waits for the next event from the server.
+  ///
+  /// Fails with [`Error::DisplayUnavailable`] once the server closes the connection, so
+  /// the trigger waiting on it can be restarted on a new one.
+  pub(crate) async fn next_event(&self) -> Result<XEvent> {
+    loop {
+      // Xlib stops reading from a connection after an I/O error, and older versions exit
+      // on it, so the socket is checked before Xlib reads from it.
+      if !is_alive(self.display) {
+        return Err(Error::DisplayUnavailable(CONNECTION_CLOSED.to_string()));
+      }
+
+      if unsafe { XPending(self.display) } > 0 {
+        let mut event = MaybeUninit::<XEvent>::uninit();
+        unsafe {
+          XNextEvent(self.display, event.as_mut_ptr());
+          return Ok(event.assume_init());
+        }
+      }
+
+      // Readiness is cleared before the next `XPending`, which reads everything buffered
+      // on the socket, so no wakeup is lost between the two.
+      let Some(fd) = &self.fd else {
+        return Err(Error::DisplayUnavailable(CONNECTION_CLOSED.to_string()));
+      };
+      let mut guard = fd.readable().await?;
+      guard.clear_ready();
+    }
+  }
+
+  /// Runs `f` and returns the X error code it caused, if any.
+  pub(crate) fn trap_errors<R>(&self, f: impl FnOnce(*mut Display) -> R) -> (R, Option<u8>) {
+    trap_errors(self.display, || f(self.display))
+  }
+}
+
+impl Drop for XEventConnection {
+  fn drop(&mut self) {
+    drop(self.fd.take());
+    close_display(self.display);
+  }
+}
@@ -0,0 +1,100 @@
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_uchar, c_ulong};
+use std::ptr;
+use x11::xlib::{Atom, Display, False, XA_WINDOW, XFree, XGetWindowProperty, XInternAtom};
+
+/// `_NET_ACTIVE_WINDOW`, set on the root window by EWMH-compliant window managers.
+pub(crate) const NET_ACTIVE_WINDOW: &CStr = c"_NET_ACTIVE_WINDOW";
+/// `_NET_CLIENT_LIST`, the managed top-level windows in mapping order.
+pub(crate) const NET_CLIENT_LIST: &CStr = c"_NET_CLIENT_LIST";
+/// `_NET_CURRENT_DESKTOP`, the index of the desktop being shown.
+pub(crate) const NET_CURRENT_DESKTOP: &CStr = c"_NET_CURRENT_DESKTOP";
+/// `_NET_FRAME_EXTENTS`, the size of the frame the window manager drew around a window.
+pub(crate) const NET_FRAME_EXTENTS: &CStr = c"_NET_FRAME_EXTENTS";
+/// `_NET_MOVERESIZE_WINDOW`, asks the window manager to move or resize a window.
+pub(crate) const NET_MOVERESIZE_WINDOW: &CStr = c"_NET_MOVERESIZE_WINDOW";
+/// `_NET_RESTACK_WINDOW`, asks the window manager to change a window's stacking order.
+pub(crate) const NET_RESTACK_WINDOW: &CStr = c"_NET_RESTACK_WINDOW";
+/// `_NET_SUPPORTED`, the hints the window manager supports.
+pub(crate) const NET_SUPPORTED: &CStr = c"_NET_SUPPORTED";
+/// `_NET_WORKAREA`, the area of each desktop left to windows by panels and docks.
+pub(crate) const NET_WORKAREA: &CStr = c"_NET_WORKAREA";
+pub(crate) const NET_WM_PID: &CStr = c"_NET_WM_PID";
+pub(crate) const NET_WM_STATE: &CStr = c"_NET_WM_STATE";
+pub(crate) const NET_WM_STATE_HIDDEN: &CStr = c"_NET_WM_STATE_HIDDEN";
+pub(crate) const NET_WM_STATE_MAXIMIZED_HORZ: &CStr = c"_NET_WM_STATE_MAXIMIZED_HORZ";
+pub(crate) const NET_WM_STATE_MAXIMIZED_VERT: &CStr = c"_NET_WM_STATE_MAXIMIZED_VERT";
+pub(crate) const WM_DELETE_WINDOW: &CStr = c"WM_DELETE_WINDOW";
+pub(crate) const WM_PROTOCOLS: &CStr = c"WM_PROTOCOLS";
+
+/// `_NET_WM_STATE` client message action removing a state.
+pub(crate) const NET_WM_STATE_REMOVE: i64 = 0;
+/// `_NET_WM_STATE` client message action adding a state.
+pub(crate) const NET_WM_STATE_ADD: i64 = 1;
+/// Source indication for client messages sent on behalf of the user, like a pager.
+pub(crate) const SOURCE_PAGER: i64 = 2;
+
+/// Returns the atom with the given name, or `None` if no client created it yet.
+pub(crate) fn existing_atom(display: *mut Display, name: &CStr) -> Option<Atom> {
+  let atom = unsafe { XInternAtom(display, name.as_ptr(), 1) };
+  (atom != 0).then_some(atom)
+}
+
+/// Reads a property made of 32-bit values, such as a `WINDOW`, `ATOM` or `CARDINAL` list.
+///
+/// Returns `None` if the property is not set or has a different type or format.
+pub(crate) fn long_property(
+  display: *mut Display,
+  window: x11::xlib::Window,
+  property: Atom,
+  kind: Atom,
+) -> Option<Vec<c_ulong>> {
+  let mut actual_type: Atom = 0;
+  let mut actual_format: c_int = 0;
+  let mut items: c_ulong = 0;
+  let mut bytes_after: c_ulong = 0;
+  let mut data: *mut c_uchar = ptr::null_mut();
+
+  let status = unsafe {
+    XGetWindowProperty(
+      display,
+      window,
+      property,
+      0,
+      // Length in 32-bit units; large enough for any list we read.
+      4096,
+      False,
+      kind,
+      &mut actual_type,
+      &mut actual_format,
+      &mut items,
+      &mut bytes_after,
+      &mut data,
+    )
+  };
+
+  if status != 0 || data.is_null() {
+    return None;
+  }
+
+  let values = (actual_type == kind && actual_format == 32).then(|| {
+    // 32-bit properties are returned as an array of C longs.
+    unsafe { std::slice::from_raw_parts(data.cast::<c_ulong>(), items as usize) }.to_vec()
+  });
+
+  unsafe { XFree(data.cast()) };
+  values
+}
+
+/// Reads a single `WINDOW` property, such as `_NET_ACTIVE_WINDOW`.
+///
+/// Returns `None` if the property is not set at all, and `Some(0)` if it is set to no window.
+pub(crate) fn window_property(
+  display: *mut Display,
+  window: x11::xlib::Window,
+  property: Atom,
+) -> Option<x11::xlib::Window> {
+  long_property(display, window, property, XA_WINDOW)?
+    .first()
+    .copied()
+}
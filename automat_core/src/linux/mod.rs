@@ -0,0 +1,13 @@
+//! X11 and kernel helpers shared by the Linux backends.
+
+mod connection;
+mod events;
+mod ewmh;
+mod proc_connector;
+mod settings;
+
+pub(crate) use connection::*;
+pub(crate) use events::*;
+pub(crate) use ewmh::*;
+pub(crate) use proc_connector::*;
+pub(crate) use settings::*;
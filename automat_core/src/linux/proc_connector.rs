@@ -0,0 +1,397 @@
+use crate::Result;
+use std::io;
+use std::mem::size_of;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+
+/// `CN_IDX_PROC` and `CN_VAL_PROC`, the connector id of process events.
+const CN_IDX_PROC: u32 = 1;
+const CN_VAL_PROC: u32 = 1;
+
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_CN_MCAST_IGNORE: u32 = 2;
+
+const PROC_EVENT_NONE: u32 = 0x0000_0000;
+const PROC_EVENT_EXEC: u32 = 0x0000_0002;
+const PROC_EVENT_EXIT: u32 = 0x8000_0000;
+
+const NLMSG_HEADER_LEN: usize = 16;
+/// `struct cn_msg` without its data.
+const CN_MSG_LEN: usize = 20;
+/// How long the kernel gets to acknowledge a subscription.
+const ACK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Offset of the event data in `struct proc_event`, after `what`, `cpu` and
+/// `timestamp_ns`.
+const PROC_EVENT_DATA: usize = 16;
+
+/// A process event received from the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcEvent {
+  /// A process started running a new program.
+  Exec { pid: u32, latency: Duration },
+  /// A process exited, with its wait status.
+  Exit {
+    pid: u32,
+    status: u32,
+    latency: Duration,
+  },
+  /// The socket buffer overflowed and events were dropped.
+  Lost,
+}
+
+/// A subscription to the kernel's process connector, which reports processes
+/// executing programs and exiting as it happens.
+///
+/// Subscribing needs `CAP_NET_ADMIN` in the initial network namespace, so it usually
+/// fails for unprivileged users and inside containers.
+pub(crate) struct ProcConnector {
+  fd: AsyncFd<OwnedFd>,
+}
+
+impl ProcConnector {
+  /// Subscribes to process events, failing unless the kernel acknowledges it.
+  pub(crate) async fn open() -> Result<Self> {
+    // SAFETY: plain socket calls; the fd is owned right after it is created.
+    let fd = unsafe {
+      libc::socket(
+        libc::AF_NETLINK,
+        libc::SOCK_DGRAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+        libc::NETLINK_CONNECTOR,
+      )
+    };
+    if fd < 0 {
+      return Err(io::Error::last_os_error().into());
+    }
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // Bursts of short-lived processes easily fill the default buffer.
+    let size: libc::c_int = 1024 * 1024;
+    unsafe {
+      libc::setsockopt(
+        fd.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_RCVBUF,
+        std::ptr::from_ref(&size).cast(),
+        size_of::<libc::c_int>() as libc::socklen_t,
+      )
+    };
+
+    // SAFETY: `sockaddr_nl` is plain data.
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups = CN_IDX_PROC;
+    let bound = unsafe {
+      libc::bind(
+        fd.as_raw_fd(),
+        std::ptr::from_ref(&addr).cast(),
+        size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+      )
+    };
+    if bound != 0 {
+      return Err(io::Error::last_os_error().into());
+    }
+
+    send_op(&fd, PROC_CN_MCAST_LISTEN)?;
+    let connector = Self {
+      fd: AsyncFd::new(fd)?,
+    };
+
+    // Inside a user or pid namespace the kernel ignores the request without an error,
+    // so only its acknowledgement shows that events will arrive.
+    match tokio::time::timeout(ACK_TIMEOUT, connector.ack()).await {
+      Ok(acked) => acked?,
+      Err(_elapsed) => {
+        return Err(
+          io::Error::new(
+            io::ErrorKind::TimedOut,
+            "the kernel did not acknowledge the process connector subscription",
+          )
+          .into(),
+        );
+      }
+    }
+    Ok(connector)
+  }
+
+  /// Waits for the next message from the kernel and returns its events.
+  pub(crate) async fn next_events(&self) -> Result<Vec<ProcEvent>> {
+    let mut buf = [0u8; 8192];
+    match self.recv(&mut buf).await {
+      Ok(len) => Ok(parse(&buf[..len])),
+      Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => Ok(vec![ProcEvent::Lost]),
+      Err(e) => Err(e.into()),
+    }
+  }
+
+  /// Waits for the kernel to acknowledge the subscription, returning the error it
+  /// reports.
+  async fn ack(&self) -> Result<()> {
+    let mut buf = [0u8; 8192];
+    loop {
+      // Events before the acknowledgement are dropped; the trigger lists the running
+      // processes once subscribed.
+      let len = match self.recv(&mut buf).await {
+        Ok(len) => len,
+        Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => continue,
+        Err(e) => return Err(e.into()),
+      };
+
+      match parse_ack(&buf[..len]) {
+        Some(0) => return Ok(()),
+        Some(err) => return Err(io::Error::from_raw_os_error(err as i32).into()),
+        None => {}
+      }
+    }
+  }
+
+  /// Receives the next datagram sent by the kernel, returning its length.
+  async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+      let mut guard = self.fd.readable().await?;
+      match guard.try_io(|fd| recv_from_kernel(fd.get_ref(), buf)) {
+        Ok(Ok(Some(len))) => return Ok(len),
+        // Sent by a process rather than the kernel.
+        Ok(Ok(None)) => {}
+        Ok(Err(e)) => return Err(e),
+        Err(_would_block) => {}
+      }
+    }
+  }
+}
+
+impl Drop for ProcConnector {
+  fn drop(&mut self) {
+    // The kernel only builds events while someone listens.
+    let _ = send_op(self.fd.get_ref(), PROC_CN_MCAST_IGNORE);
+  }
+}
+
+/// Sends a `PROC_CN_MCAST_*` operation to the connector.
+fn send_op(fd: &OwnedFd, op: u32) -> io::Result<()> {
+  let len = NLMSG_HEADER_LEN + CN_MSG_LEN + size_of::<u32>();
+  let mut msg = Vec::with_capacity(len);
+  // struct nlmsghdr
+  msg.extend_from_slice(&(len as u32).to_ne_bytes());
+  msg.extend_from_slice(&(libc::NLMSG_DONE as u16).to_ne_bytes());
+  msg.extend_from_slice(&0u16.to_ne_bytes());
+  msg.extend_from_slice(&0u32.to_ne_bytes());
+  msg.extend_from_slice(&std::process::id().to_ne_bytes());
+  // struct cn_msg
+  msg.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+  msg.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+  msg.extend_from_slice(&0u32.to_ne_bytes());
+  // Echoed plus one in the acknowledgement, telling ours apart from other listeners'.
+  msg.extend_from_slice(&std::process::id().to_ne_bytes());
+  msg.extend_from_slice(&(size_of::<u32>() as u16).to_ne_bytes());
+  msg.extend_from_slice(&0u16.to_ne_bytes());
+  msg.extend_from_slice(&op.to_ne_bytes());
+
+  let sent = unsafe { libc::send(fd.as_raw_fd(), msg.as_ptr().cast(), msg.len(), 0) };
+  if sent < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  Ok(())
+}
+
+/// Receives one datagram, returning its length, or `None` if it was not sent by the
+/// kernel.
+fn recv_from_kernel(fd: &OwnedFd, buf: &mut [u8]) -> io::Result<Option<usize>> {
+  // SAFETY: `sockaddr_nl` is plain data, and the buffer and address outlive the call.
+  let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+  let mut addr_len = size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+  let len = unsafe {
+    libc::recvfrom(
+      fd.as_raw_fd(),
+      buf.as_mut_ptr().cast(),
+      buf.len(),
+      0,
+      std::ptr::from_mut(&mut addr).cast(),
+      &mut addr_len,
+    )
+  };
+
+  if len < 0 {
+    return Err(io::Error::last_os_error());
+  }
+  Ok((addr.nl_pid == 0).then_some(len as usize))
+}
+
+/// Splits a datagram into its netlink messages, returning the `struct cn_msg` of each.
+fn messages(mut buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+  std::iter::from_fn(move || {
+    let msg_len = read_u32(buf, 0)? as usize;
+    if msg_len < NLMSG_HEADER_LEN || msg_len > buf.len() {
+      return None;
+    }
+    let msg = &buf[NLMSG_HEADER_LEN..msg_len];
+    // Messages are padded to 4 bytes.
+    buf = &buf[msg_len.next_multiple_of(4).min(buf.len())..];
+    Some(msg)
+  })
+}
+
+/// Returns the `struct proc_event` in a connector message, if it is one.
+fn proc_event(cn_msg: &[u8]) -> Option<&[u8]> {
+  if read_u32(cn_msg, 0)? != CN_IDX_PROC || read_u32(cn_msg, 4)? != CN_VAL_PROC {
+    return None;
+  }
+  cn_msg.get(CN_MSG_LEN..)
+}
+
+/// Finds the acknowledgement of our subscription in a datagram, returning its error.
+fn parse_ack(buf: &[u8]) -> Option<u32> {
+  messages(buf).find_map(|cn_msg| {
+    let event = proc_event(cn_msg)?;
+    let ours = read_u32(cn_msg, 12)? == std::process::id().wrapping_add(1);
+    if !ours || read_u32(event, 0)? != PROC_EVENT_NONE {
+      return None;
+    }
+    read_u32(event, PROC_EVENT_DATA)
+  })
+}
+
+/// Parses the netlink messages in a datagram, skipping threads and events not needed.
+fn parse(buf: &[u8]) -> Vec<ProcEvent> {
+  let now = monotonic_now();
+  messages(buf)
+    .filter_map(|cn_msg| parse_event(cn_msg, now))
+    .collect()
+}
+
+fn parse_event(cn_msg: &[u8], now: Duration) -> Option<ProcEvent> {
+  let event = proc_event(cn_msg)?;
+  let what = read_u32(event, 0)?;
+  let timestamp = Duration::from_nanos(u64::from_ne_bytes(event.get(8..16)?.try_into().ok()?));
+  let latency = now.saturating_sub(timestamp);
+  let data = event.get(PROC_EVENT_DATA..)?;
+  let (pid, tgid) = (read_u32(data, 0)?, read_u32(data, 4)?);
+
+  // Threads are reported too, with the id of their process as `tgid`.
+  if pid != tgid {
+    return None;
+  }
+
+  match what {
+    PROC_EVENT_EXEC => Some(ProcEvent::Exec { pid, latency }),
+    PROC_EVENT_EXIT => Some(ProcEvent::Exit {
+      pid,
+      status: read_u32(data, 8)?,
+      latency,
+    }),
+    _ => None,
+  }
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+  Some(u32::from_ne_bytes(
+    buf.get(offset..offset + 4)?.try_into().ok()?,
+  ))
+}
+
+/// Time on the clock the kernel stamps events with.
+fn monotonic_now() -> Duration {
+  // SAFETY: `timespec` is plain data and a valid out pointer.
+  let mut now: libc::timespec = unsafe { std::mem::zeroed() };
+  unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut now) };
+  Duration::new(now.tv_sec as u64, now.tv_nsec as u32)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Builds a netlink message from the kernel holding a `struct proc_event`.
+  fn message(ack: u32, what: u32, timestamp: Duration, data: &[u32]) -> Vec<u8> {
+    let mut event = Vec::new();
+    event.extend_from_slice(&what.to_ne_bytes());
+    event.extend_from_slice(&0u32.to_ne_bytes());
+    event.extend_from_slice(&(timestamp.as_nanos() as u64).to_ne_bytes());
+    for value in data {
+      event.extend_from_slice(&value.to_ne_bytes());
+    }
+
+    let len = NLMSG_HEADER_LEN + CN_MSG_LEN + event.len();
+    let mut msg = Vec::new();
+    msg.extend_from_slice(&(len as u32).to_ne_bytes());
+    msg.extend_from_slice(&[0; NLMSG_HEADER_LEN - 4]);
+    msg.extend_from_slice(&CN_IDX_PROC.to_ne_bytes());
+    msg.extend_from_slice(&CN_VAL_PROC.to_ne_bytes());
+    msg.extend_from_slice(&0u32.to_ne_bytes());
+    msg.extend_from_slice(&ack.to_ne_bytes());
+    msg.extend_from_slice(&(event.len() as u16).to_ne_bytes());
+    msg.extend_from_slice(&0u16.to_ne_bytes());
+    msg.extend_from_slice(&event);
+    msg
+  }
+
+  fn parse_at(buf: &[u8], now: Duration) -> Vec<ProcEvent> {
+    messages(buf)
+      .filter_map(|cn_msg| parse_event(cn_msg, now))
+      .collect()
+  }
+
+  #[test]
+  fn parses_exec_and_exit() {
+    let mut buf = message(0, PROC_EVENT_EXEC, Duration::from_secs(10), &[42, 42]);
+    buf.extend(message(
+      0,
+      PROC_EVENT_EXIT,
+      Duration::from_secs(11),
+      &[42, 42, 256, 17],
+    ));
+
+    assert_eq!(
+      parse_at(&buf, Duration::from_secs(12)),
+      [
+        ProcEvent::Exec {
+          pid: 42,
+          latency: Duration::from_secs(2)
+        },
+        ProcEvent::Exit {
+          pid: 42,
+          status: 256,
+          latency: Duration::from_secs(1)
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn skips_threads_and_other_events() {
+    let thread = message(0, PROC_EVENT_EXEC, Duration::ZERO, &[43, 42]);
+    let fork = message(0, 0x0000_0001, Duration::ZERO, &[42, 42]);
+    assert_eq!(parse_at(&thread, Duration::ZERO), []);
+    assert_eq!(parse_at(&fork, Duration::ZERO), []);
+
+    let mut other = message(0, PROC_EVENT_EXEC, Duration::ZERO, &[42, 42]);
+    other[NLMSG_HEADER_LEN..NLMSG_HEADER_LEN + 4].copy_from_slice(&2u32.to_ne_bytes());
+    assert_eq!(parse_at(&other, Duration::ZERO), []);
+  }
+
+  #[test]
+  fn ignores_truncated_messages() {
+    let buf = message(0, PROC_EVENT_EXIT, Duration::ZERO, &[42, 42, 0]);
+    assert_eq!(parse_at(&buf[..buf.len() - 2], Duration::ZERO), []);
+    assert_eq!(parse_at(&buf, Duration::ZERO).len(), 1);
+  }
+
+  #[test]
+  fn finds_our_acknowledgement() {
+    let ours = std::process::id().wrapping_add(1);
+    let ack = |ack, what, err| message(ack, what, Duration::ZERO, &[err]);
+
+    assert_eq!(parse_ack(&ack(ours, PROC_EVENT_NONE, 0)), Some(0));
+    assert_eq!(
+      parse_ack(&ack(ours, PROC_EVENT_NONE, libc::EPERM as u32)),
+      Some(libc::EPERM as u32)
+    );
+    assert_eq!(parse_ack(&ack(ours + 1, PROC_EVENT_NONE, 0)), None);
+    assert_eq!(parse_ack(&ack(ours, PROC_EVENT_EXEC, 0)), None);
+
+    let mut buf = message(0, PROC_EVENT_EXEC, Duration::ZERO, &[42, 42]);
+    buf.extend(ack(ours, PROC_EVENT_NONE, 0));
+    assert_eq!(parse_ack(&buf), Some(0));
+  }
+}
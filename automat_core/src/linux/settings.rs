@@ -0,0 +1,232 @@
+use crate::linux::{existing_atom, with_connection};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_int, c_uchar, c_ulong};
+use std::ptr;
+use std::time::Duration;
+use x11::xlib::{
+  AnyPropertyType, Atom, Display, False, XDefaultScreen, XFree, XGetDefault, XGetSelectionOwner,
+  XGetWindowProperty,
+};
+
+/// `_XSETTINGS_SETTINGS`, the settings published on the XSETTINGS manager's window.
+const XSETTINGS_SETTINGS: &CStr = c"_XSETTINGS_SETTINGS";
+
+/// XSETTINGS setting types.
+const SETTING_INTEGER: u8 = 0;
+const SETTING_STRING: u8 = 1;
+const SETTING_COLOR: u8 = 2;
+
+/// Gets the double-click time the desktop is configured with.
+///
+/// Reads `Net/DoubleClickTime` from XSETTINGS, which the GNOME, Xfce and MATE settings
+/// daemons publish, then the `multiClickTime` X resource. Returns `None` if neither is
+/// set.
+pub(crate) fn double_click_time() -> Option<Duration> {
+  with_connection(|conn| {
+    let display = conn.display();
+    xsettings_integer(display, "Net/DoubleClickTime")
+      .or_else(|| resource(display, c"multiClickTime")?.parse().ok())
+  })
+  .ok()
+  .flatten()
+  .and_then(|millis| u64::try_from(millis).ok())
+  .filter(|millis| *millis > 0)
+  .map(Duration::from_millis)
+}
+
+/// Reads an integer setting from the XSETTINGS manager of the default screen.
+fn xsettings_integer(display: *mut Display, name: &str) -> Option<i32> {
+  let screen = unsafe { XDefaultScreen(display) };
+  let selection = CString::new(format!("_XSETTINGS_S{screen}")).ok()?;
+  let owner = unsafe { XGetSelectionOwner(display, existing_atom(display, &selection)?) };
+  if owner == 0 {
+    return None;
+  }
+
+  let settings = byte_property(display, owner, existing_atom(display, XSETTINGS_SETTINGS)?)?;
+  find_integer(&settings, name)
+}
+
+/// Finds an integer setting in the serialized XSETTINGS property.
+///
+/// The layout is described in the XSETTINGS specification: a byte order, a serial and
+/// a count, then each setting as a type, its name padded to 4 bytes, a serial and the
+/// value.
+fn find_integer(data: &[u8], name: &str) -> Option<i32> {
+  // X protocol byte orders: 0 is LSBFirst, 1 is MSBFirst.
+  let big_endian = *data.first()? == 1;
+  let card16 = |at: usize| {
+    let bytes = data.get(at..at + 2)?.try_into().ok()?;
+    Some(if big_endian {
+      u16::from_be_bytes(bytes)
+    } else {
+      u16::from_le_bytes(bytes)
+    })
+  };
+  let card32 = |at: usize| {
+    let bytes = data.get(at..at + 4)?.try_into().ok()?;
+    Some(if big_endian {
+      u32::from_be_bytes(bytes)
+    } else {
+      u32::from_le_bytes(bytes)
+    })
+  };
+
+  let count = card32(8)?;
+  let mut at = 12;
+  for _ in 0..count {
+    let kind = *data.get(at)?;
+    let name_len = usize::from(card16(at + 2)?);
+    let setting = data.get(at + 4..at + 4 + name_len)?;
+    // Skip the header, the padded name and the last-change serial.
+    at += 4 + name_len.next_multiple_of(4) + 4;
+
+    match kind {
+      SETTING_INTEGER if setting == name.as_bytes() => return Some(card32(at)? as i32),
+      SETTING_INTEGER => at += 4,
+      SETTING_STRING => at += 4 + (card32(at)? as usize).next_multiple_of(4),
+      SETTING_COLOR => at += 8,
+      _ => return None,
+    }
+  }
+  None
+}
+
+/// Looks up an X resource from `RESOURCE_MANAGER`, as set by `xrdb`.
+fn resource(display: *mut Display, option: &CStr) -> Option<String> {
+  // The value is owned by Xlib's resource database and must not be freed.
+  let value = unsafe { XGetDefault(display, c"automat".as_ptr(), option.as_ptr()) };
+  if value.is_null() {
+    return None;
+  }
+  let value = unsafe { CStr::from_ptr(value) };
+  Some(value.to_string_lossy().trim().to_owned())
+}
+
+/// Reads a property made of bytes, whatever its type.
+fn byte_property(
+  display: *mut Display,
+  window: x11::xlib::Window,
+  property: Atom,
+) -> Option<Vec<u8>> {
+  let mut actual_type: Atom = 0;
+  let mut actual_format: c_int = 0;
+  let mut items: c_ulong = 0;
+  let mut bytes_after: c_ulong = 0;
+  let mut data: *mut c_uchar = ptr::null_mut();
+
+  let status = unsafe {
+    XGetWindowProperty(
+      display,
+      window,
+      property,
+      0,
+      // Length in 32-bit units; the server returns at most what the property holds.
+      0x7fff_ffff,
+      False,
+      AnyPropertyType as Atom,
+      &mut actual_type,
+      &mut actual_format,
+      &mut items,
+      &mut bytes_after,
+      &mut data,
+    )
+  };
+
+  if status != 0 || data.is_null() {
+    return None;
+  }
+
+  let bytes = (actual_format == 8)
+    .then(|| unsafe { std::slice::from_raw_parts(data, items as usize) }.to_vec());
+
+  unsafe { XFree(data.cast()) };
+  bytes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Serializes settings the way an XSETTINGS manager does, in the given byte order.
+  fn serialize(big_endian: bool, settings: &[(u8, &str, &[u8])]) -> Vec<u8> {
+    let card16 = |value: u16| {
+      if big_endian {
+        value.to_be_bytes()
+      } else {
+        value.to_le_bytes()
+      }
+    };
+    let card32 = |value: u32| {
+      if big_endian {
+        value.to_be_bytes()
+      } else {
+        value.to_le_bytes()
+      }
+    };
+    let pad = |data: &mut Vec<u8>| data.resize(data.len().next_multiple_of(4), 0);
+
+    let mut data = vec![u8::from(big_endian), 0, 0, 0];
+    data.extend(card32(1));
+    data.extend(card32(settings.len() as u32));
+    for (kind, name, value) in settings {
+      data.extend([*kind, 0]);
+      data.extend(card16(name.len() as u16));
+      data.extend(name.as_bytes());
+      pad(&mut data);
+      data.extend(card32(0));
+      match *kind {
+        SETTING_STRING => {
+          data.extend(card32(value.len() as u32));
+          data.extend(*value);
+          pad(&mut data);
+        }
+        SETTING_INTEGER => data.extend(card32(u32::from_ne_bytes((*value).try_into().unwrap()))),
+        _ => data.extend(*value),
+      }
+    }
+    data
+  }
+
+  fn settings(big_endian: bool) -> Vec<u8> {
+    serialize(
+      big_endian,
+      &[
+        (SETTING_STRING, "Net/ThemeName", b"Adwaita-dark"),
+        (SETTING_COLOR, "Gtk/Color", &[0xff; 8]),
+        (SETTING_INTEGER, "Net/CursorBlink", &1i32.to_ne_bytes()),
+        (
+          SETTING_INTEGER,
+          "Net/DoubleClickTime",
+          &250i32.to_ne_bytes(),
+        ),
+      ],
+    )
+  }
+
+  #[test]
+  fn finds_integers_after_other_settings() {
+    for big_endian in [false, true] {
+      let data = settings(big_endian);
+      assert_eq!(find_integer(&data, "Net/DoubleClickTime"), Some(250));
+      assert_eq!(find_integer(&data, "Net/CursorBlink"), Some(1));
+    }
+  }
+
+  #[test]
+  fn ignores_missing_and_non_integer_settings() {
+    let data = settings(false);
+    assert_eq!(find_integer(&data, "Net/ThemeName"), None);
+    assert_eq!(find_integer(&data, "Net/DndDragThreshold"), None);
+  }
+
+  #[test]
+  fn rejects_truncated_data() {
+    let data = settings(false);
+    assert_eq!(
+      find_integer(&data[..data.len() - 2], "Net/DoubleClickTime"),
+      None
+    );
+    assert_eq!(find_integer(&[], "Net/DoubleClickTime"), None);
+  }
+}
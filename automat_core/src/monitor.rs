@@ -0,0 +1,232 @@
+use crate::{DisplayInfo, Result, Window, WindowGeometry};
+
+/// A display connected to the computer, and where it sits in the screen layout.
+///
+/// Positions and sizes are in the same screen coordinates as windows and the mouse, so
+/// they can be compared with [`Window::geometry`] and [`mouse_position`].
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let (x, y) = mouse_position()?;
+/// if let Some(monitor) = Monitor::at_point(x, y)? {
+///   println!("The cursor is on {} ({:?})", monitor.name(), monitor.bounds());
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// [`mouse_position`]: crate::mouse_position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+  id: u32,
+  name: String,
+  bounds: WindowGeometry,
+  work_area: WindowGeometry,
+  scale_factor: f32,
+  primary: bool,
+}
+
+impl Monitor {
+  /// Returns all connected monitors.
+  pub fn all() -> Result<Vec<Self>> {
+    let infos = DisplayInfo::all()?;
+    let work_area = desktop_work_area();
+    Ok(
+      infos
+        .into_iter()
+        .map(|info| Self::from_info(info, work_area))
+        .collect(),
+    )
+  }
+
+  /// Returns the primary monitor, the one holding the taskbar or menu bar by default.
+  pub fn primary() -> Result<Option<Self>> {
+    Ok(Self::all()?.into_iter().find(Self::is_primary))
+  }
+
+  /// Returns the monitor showing the given point, if any.
+  pub fn at_point(x: i32, y: i32) -> Result<Option<Self>> {
+    Ok(
+      Self::all()?
+        .into_iter()
+        .find(|monitor| monitor.bounds.contains(x, y)),
+    )
+  }
+
+  /// Returns the monitor showing the largest part of the window, if it is on one.
+  pub fn for_window(window: &Window) -> Result<Option<Self>> {
+    let Some(geometry) = window.geometry() else {
+      return Ok(None);
+    };
+    Ok(
+      Self::all()?
+        .into_iter()
+        .map(|monitor| (overlap(&monitor.bounds, &geometry), monitor))
+        .filter(|(area, _)| *area > 0)
+        .max_by_key(|(area, _)| *area)
+        .map(|(_, monitor)| monitor),
+    )
+  }
+
+  fn from_info(info: DisplayInfo, desktop_work_area: Option<WindowGeometry>) -> Self {
+    let bounds = WindowGeometry::new(info.x, info.y, info.width, info.height);
+    let work_area = monitor_work_area(&bounds, desktop_work_area).unwrap_or(bounds);
+
+    Self {
+      id: info.id,
+      name: info.name,
+      bounds,
+      work_area,
+      scale_factor: info.scale_factor,
+      primary: info.is_primary,
+    }
+  }
+
+  /// Returns the identifier the platform gives the monitor.
+  pub fn id(&self) -> u32 {
+    self.id
+  }
+
+  /// Returns the name the platform gives the monitor's output, such as `HDMI-1`.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the area the monitor covers.
+  pub fn bounds(&self) -> WindowGeometry {
+    self.bounds
+  }
+
+  /// Returns the part of the monitor left to windows, without panels, docks and
+  /// taskbars.
+  ///
+  /// On Linux this comes from the window manager's `_NET_WORKAREA`, which only
+  /// accounts for panels along the edges of the whole desktop. On macOS it is the same
+  /// as the [bounds](Self::bounds).
+  pub fn work_area(&self) -> WindowGeometry {
+    self.work_area
+  }
+
+  /// Returns how many physical pixels make up one logical pixel.
+  pub fn scale_factor(&self) -> f32 {
+    self.scale_factor
+  }
+
+  /// Returns whether this is the primary monitor.
+  pub fn is_primary(&self) -> bool {
+    self.primary
+  }
+}
+
+impl Window {
+  /// Gets the monitor showing the largest part of this window.
+  pub fn monitor(&self) -> Result<Option<Monitor>> {
+    Monitor::for_window(self)
+  }
+}
+
+/// The area two rectangles have in common, in square pixels.
+fn overlap(a: &WindowGeometry, b: &WindowGeometry) -> u64 {
+  intersection(a, b).map_or(0, |area| u64::from(area.width) * u64::from(area.height))
+}
+
+fn intersection(a: &WindowGeometry, b: &WindowGeometry) -> Option<WindowGeometry> {
+  let right = |r: &WindowGeometry| i64::from(r.x) + i64::from(r.width);
+  let bottom = |r: &WindowGeometry| i64::from(r.y) + i64::from(r.height);
+
+  let (left, top) = (a.x.max(b.x), a.y.max(b.y));
+  let width = right(a).min(right(b)) - i64::from(left);
+  let height = bottom(a).min(bottom(b)) - i64::from(top);
+  (width > 0 && height > 0).then(|| WindowGeometry::new(left, top, width as u32, height as u32))
+}
+
+/// Gets the work area of the current desktop from `_NET_WORKAREA`.
+///
+/// Returns `None` if the window manager does not set it.
+#[cfg(target_os = "linux")]
+fn desktop_work_area() -> Option<WindowGeometry> {
+  use crate::linux::{
+    NET_CURRENT_DESKTOP, NET_WORKAREA, existing_atom, long_property, with_connection,
+  };
+  use x11::xlib::XA_CARDINAL;
+
+  with_connection(|conn| {
+    let (display, root) = (conn.display(), conn.root());
+    let current = existing_atom(display, NET_CURRENT_DESKTOP)
+      .and_then(|atom| long_property(display, root, atom, XA_CARDINAL))
+      .and_then(|desktop| desktop.first().copied())
+      .unwrap_or(0) as usize;
+    let areas = long_property(
+      display,
+      root,
+      existing_atom(display, NET_WORKAREA)?,
+      XA_CARDINAL,
+    )?;
+
+    // Four values, x, y, width and height, per desktop.
+    let area = areas.chunks_exact(4).nth(current)?;
+    Some(WindowGeometry::new(
+      area[0] as i32,
+      area[1] as i32,
+      area[2] as u32,
+      area[3] as u32,
+    ))
+  })
+  .ok()
+  .flatten()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn desktop_work_area() -> Option<WindowGeometry> {
+  None
+}
+
+/// The part of the desktop work area on the monitor.
+#[cfg(target_os = "linux")]
+fn monitor_work_area(
+  bounds: &WindowGeometry,
+  desktop_work_area: Option<WindowGeometry>,
+) -> Option<WindowGeometry> {
+  intersection(bounds, &desktop_work_area?)
+}
+
+/// Gets the work area of the monitor with `GetMonitorInfoW`.
+#[cfg(target_os = "windows")]
+fn monitor_work_area(bounds: &WindowGeometry, _: Option<WindowGeometry>) -> Option<WindowGeometry> {
+  use windows::Win32::Foundation::POINT;
+  use windows::Win32::Graphics::Gdi::{
+    GetMonitorInfoW, MONITOR_DEFAULTTONULL, MONITORINFO, MonitorFromPoint,
+  };
+
+  unsafe {
+    let center = POINT {
+      x: bounds.x + (bounds.width / 2) as i32,
+      y: bounds.y + (bounds.height / 2) as i32,
+    };
+    let monitor = MonitorFromPoint(center, MONITOR_DEFAULTTONULL);
+    if monitor.is_invalid() {
+      return None;
+    }
+
+    let mut info = MONITORINFO {
+      cbSize: size_of::<MONITORINFO>() as u32,
+      ..Default::default()
+    };
+    if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+      return None;
+    }
+
+    let work = info.rcWork;
+    Some(WindowGeometry::new(
+      work.left,
+      work.top,
+      (work.right - work.left) as u32,
+      (work.bottom - work.top) as u32,
+    ))
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn monitor_work_area(_: &WindowGeometry, _: Option<WindowGeometry>) -> Option<WindowGeometry> {
+  None
+}
@@ -1,31 +1,97 @@
 use crate::triggers::context::TriggerContext;
-use crate::{callback, get_clipboard_text, pair_api, send_err, Result, Trigger, TriggerRuntime};
+use crate::{
+  callback, get_clipboard_selection, pair_api, send_err, send_error, Error, Result, Trigger,
+  TriggerRuntime,
+};
 use async_trait::async_trait;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::path::PathBuf;
 use std::time::Duration;
 use derivative::Derivative;
 use tokio::time::sleep;
 
+/// A trigger that runs its callback when the content of the clipboard changes.
+///
+/// On X11, changes are delivered as soon as another application takes ownership of a
+/// selection, through the XFixes extension. Servers without XFixes, and other platforms,
+/// fall back to reading the clipboard every poll interval, 250ms by default.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct ClipboardTrigger {
-  last_content: String,
+  #[derivative(Debug = "ignore")]
+  last_content: HashMap<ClipboardSelection, Option<ClipboardEvent>>,
+  /// Selections whose last read failed, so their errors are only reported once.
+  failing: HashSet<ClipboardSelection>,
+  selections: Vec<ClipboardSelection>,
   poll_interval: Duration,
   #[derivative(Debug = "ignore")]
-  callback: ClipboardCallback<TriggerContext<ClipboardEvent>>,
+  callback: ClipboardCallback<TriggerContext<ClipboardChange>>,
+}
+
+/// A selection the clipboard trigger can watch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardSelection {
+  /// The clipboard filled by explicit copy and cut.
+  Clipboard,
+  /// The text last selected with the mouse, pasted with a middle click. Only X11 and
+  /// Wayland have one.
+  Primary,
+}
+
+/// A change reported by [`ClipboardTrigger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardChange {
+  pub selection: ClipboardSelection,
+  pub content: ClipboardEvent,
 }
 
-#[derive(Debug, Clone)]
-// New value of the clipboard.
-pub struct ClipboardEvent(String);
+/// New value of the clipboard.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ClipboardEvent {
+  Text(String),
+  /// Rich text, usually copied from a browser, with the plain text version if one was
+  /// offered along with it.
+  Html { html: String, text: Option<String> },
+  /// An image of `width` by `height` pixels, as RGBA bytes in rows from the top.
+  Image {
+    width: usize,
+    height: usize,
+    rgba: Vec<u8>,
+  },
+  /// Files copied in a file manager.
+  Files(Vec<PathBuf>),
+}
 
 impl ClipboardEvent {
-  pub fn new(content: String) -> Self {
-    Self(content)
+  /// The plain text of the content, if it has any.
+  pub fn text(&self) -> Option<&str> {
+    match self {
+      Self::Text(text) => Some(text),
+      Self::Html { text, .. } => text.as_deref(),
+      Self::Image { .. } | Self::Files(_) => None,
+    }
   }
+}
 
-  pub fn content(&self) -> &str {
-    &self.0
+impl Debug for ClipboardEvent {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Text(text) => f.debug_tuple("Text").field(text).finish(),
+      Self::Html { html, text } => f
+        .debug_struct("Html")
+        .field("html", html)
+        .field("text", text)
+        .finish(),
+      // Pixels are left out, they would flood the output.
+      Self::Image { width, height, .. } => f
+        .debug_struct("Image")
+        .field("width", width)
+        .field("height", height)
+        .finish_non_exhaustive(),
+      Self::Files(paths) => f.debug_tuple("Files").field(paths).finish(),
+    }
   }
 }
 
@@ -35,42 +101,100 @@ impl ClipboardTrigger {
   pair_api! {
     assoc
       new(f: F)
-        callback(TriggerContext<ClipboardEvent>)
+        callback(TriggerContext<ClipboardChange>)
         async => Self::with_interval(f, Duration::from_millis(250));
         blocking => Self::with_interval_blocking(f, Duration::from_millis(250));
   }
 
   pair_api! {
     assoc
+      /// Creates a trigger that reads the clipboard every `poll_interval` when changes
+      /// cannot be watched.
       with_interval(f: F, poll_interval: Duration)
-        callback(TriggerContext<ClipboardEvent>)
-        async => Self { callback: new_clipboard_callback(f), last_content: String::new(), poll_interval };
-        blocking => Self { callback: new_clipboard_callback_blocking(f), last_content: String::new(), poll_interval };
+        callback(TriggerContext<ClipboardChange>)
+        async => Self::with_callback(new_clipboard_callback(f), poll_interval);
+        blocking => Self::with_callback(new_clipboard_callback_blocking(f), poll_interval);
   }
-}
 
-#[async_trait]
-impl Trigger for ClipboardTrigger {
-  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
-    self.last_content = get_clipboard_text()?;
+  fn with_callback(
+    callback: ClipboardCallback<TriggerContext<ClipboardChange>>,
+    poll_interval: Duration,
+  ) -> Self {
+    Self {
+      last_content: HashMap::new(),
+      failing: HashSet::new(),
+      selections: vec![ClipboardSelection::Clipboard],
+      poll_interval,
+      callback,
+    }
+  }
 
-    loop {
-      if rt.shutdown.is_cancelled() {
-        break;
+  /// Sets the selections to watch. Defaults to [`ClipboardSelection::Clipboard`] only.
+  ///
+  /// The primary selection changes with every text selected with the mouse, and is never
+  /// reported on platforms without one.
+  pub fn with_selections(
+    mut self,
+    selections: impl IntoIterator<Item = ClipboardSelection>,
+  ) -> Self {
+    self.selections.clear();
+    for selection in selections {
+      if !self.selections.contains(&selection) {
+        self.selections.push(selection);
       }
-      let current_content = get_clipboard_text()?;
-
-      if current_content != self.last_content {
-        self.last_content = current_content.clone();
-        let event = ClipboardEvent(current_content);
-        let context = TriggerContext::new(event, rt.tx.clone());
-
-        send_err!(
-          (self.callback)(context).await,
-          "ClipboardTrigger",
-          &rt.tx,
-          break
-        );
+    }
+    self
+  }
+
+  /// Reads `selection` and runs the callback if its content changed since the last read.
+  ///
+  /// The first read only records the content. Returns false if the trigger should stop.
+  async fn check(&mut self, selection: ClipboardSelection, rt: &TriggerRuntime) -> bool {
+    let content = get_clipboard_selection(selection);
+    let was_failing = match content {
+      Ok(_) => self.failing.remove(&selection),
+      Err(_) => !self.failing.insert(selection),
+    };
+
+    match content {
+      Ok(content) => match self.last_content.entry(selection) {
+        Entry::Vacant(entry) => {
+          entry.insert(content);
+        }
+        Entry::Occupied(mut entry) if *entry.get() != content => {
+          entry.insert(content.clone());
+
+          // Clearing the clipboard is not a new value.
+          if let Some(content) = content {
+            let change = ClipboardChange { selection, content };
+            let context = TriggerContext::new(change, rt.tx.clone());
+            send_err!(
+              (self.callback)(context).await,
+              "ClipboardTrigger",
+              &rt.tx,
+              return false
+            );
+          }
+        }
+        Entry::Occupied(_) => {}
+      },
+      // Another application is using the clipboard, try again on the next change.
+      Err(Error::ClipboardError(arboard::Error::ClipboardOccupied)) => {}
+      Err(_) if was_failing => {}
+      Err(err) => return send_error(&rt.tx, err, "ClipboardTrigger").await,
+    }
+
+    true
+  }
+
+  async fn poll(&mut self, rt: &TriggerRuntime) -> Result<()> {
+    let selections = self.selections.clone();
+
+    loop {
+      for &selection in &selections {
+        if !self.check(selection, rt).await {
+          return Ok(());
+        }
       }
 
       tokio::select! {
@@ -81,8 +205,145 @@ impl Trigger for ClipboardTrigger {
 
     Ok(())
   }
+}
+
+#[async_trait]
+impl Trigger for ClipboardTrigger {
+  /// Runs the callback whenever the content of a watched selection changes.
+  ///
+  /// Clipboard errors are reported without stopping the trigger, once until the
+  /// selection can be read again. Content in formats that cannot be read is treated
+  /// like an empty clipboard.
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    self.last_content.clear();
+    self.failing.clear();
+
+    #[cfg(target_os = "linux")]
+    if let Some(watcher) = linux::SelectionWatcher::open(&self.selections) {
+      return watcher.run(self, &rt).await;
+    }
+
+    self.poll(&rt).await
+  }
 
   fn name(&self) -> String {
     "ClipboardTrigger".to_string()
   }
 }
+
+#[cfg(target_os = "linux")]
+mod linux {
+  use super::ClipboardTrigger;
+  use crate::linux::XEventConnection;
+  use crate::{ClipboardSelection, Result, TriggerRuntime};
+  use std::os::raw::{c_int, c_ulong};
+  use x11::xfixes::{
+    XFixesQueryExtension, XFixesQueryVersion, XFixesSelectSelectionInput,
+    XFixesSelectionNotifyEvent,
+  };
+  use x11::xlib::{Atom, False, XA_PRIMARY, XEvent, XInternAtom};
+
+  /// `XFixesSelectionNotify`, relative to the extension's first event.
+  const SELECTION_NOTIFY: c_int = 0;
+  /// `XFixesSetSelectionOwnerNotifyMask`, `XFixesSelectionWindowDestroyNotifyMask` and
+  /// `XFixesSelectionClientCloseNotifyMask`: a new owner, or the owner going away.
+  const SELECTION_OWNER_CHANGES: c_ulong = 1 | 2 | 4;
+
+  /// Follows selection owner changes through XFixes SelectionNotify events.
+  pub(super) struct SelectionWatcher {
+    conn: XEventConnection,
+    event_base: c_int,
+    selections: Vec<(Atom, ClipboardSelection)>,
+  }
+
+  impl SelectionWatcher {
+    /// Subscribes to owner changes of the selections.
+    ///
+    /// Returns `None` if the display can't be opened or the server lacks XFixes.
+    pub(super) fn open(selections: &[ClipboardSelection]) -> Option<Self> {
+      let conn = XEventConnection::open().ok()?;
+      let display = conn.display();
+
+      let (mut event_base, mut error_base) = (0, 0);
+      // SAFETY: the display is open and both out pointers are valid.
+      if unsafe { XFixesQueryExtension(display, &mut event_base, &mut error_base) } == 0 {
+        return None;
+      }
+      // The server rejects XFixes requests until the client has announced its version.
+      // libXfixes writes the server's version back through both pointers, although the
+      // binding declares `minor` as const.
+      let (mut major, mut minor) = (5, 0);
+      // SAFETY: the display is open and both out pointers are valid and writable.
+      if unsafe { XFixesQueryVersion(display, &mut major, &raw mut minor) } == 0 {
+        return None;
+      }
+
+      let selections = selections
+        .iter()
+        .map(|&selection| {
+          let atom = match selection {
+            // SAFETY: the display is open and the name is a valid C string.
+            ClipboardSelection::Clipboard => unsafe {
+              XInternAtom(display, c"CLIPBOARD".as_ptr(), False)
+            },
+            ClipboardSelection::Primary => XA_PRIMARY,
+          };
+          (atom, selection)
+        })
+        .collect::<Vec<_>>();
+
+      for (atom, _) in &selections {
+        // SAFETY: the display is open and XFixes was initialized by the version query.
+        unsafe { XFixesSelectSelectionInput(display, conn.root(), *atom, SELECTION_OWNER_CHANGES) };
+      }
+
+      Some(Self {
+        conn,
+        event_base,
+        selections,
+      })
+    }
+
+    pub(super) async fn run(
+      self,
+      trigger: &mut ClipboardTrigger,
+      rt: &TriggerRuntime,
+    ) -> Result<()> {
+      for &(_, selection) in &self.selections {
+        if !trigger.check(selection, rt).await {
+          return Ok(());
+        }
+      }
+
+      loop {
+        let selection = tokio::select! {
+          _ = rt.shutdown.cancelled() => break,
+          event = self.conn.next_event() => {
+            let event = event?;
+            if event.get_type() != self.event_base + SELECTION_NOTIFY {
+              continue;
+            }
+            // SAFETY: events of this type are XFixesSelectionNotifyEvents, which fit in
+            // the XEvent union.
+            let notify = unsafe {
+              &*std::ptr::from_ref::<XEvent>(&event).cast::<XFixesSelectionNotifyEvent>()
+            };
+            self
+              .selections
+              .iter()
+              .find(|(atom, _)| *atom == notify.selection)
+              .map(|&(_, selection)| selection)
+          }
+        };
+
+        if let Some(selection) = selection
+          && !trigger.check(selection, rt).await
+        {
+          break;
+        }
+      }
+
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,251 @@
+use crate::{Error, Result};
+use notify::{
+  Config, Event, EventHandler, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hasher};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+
+/// How a watched path is observed.
+///
+/// Native watchers see nothing on network and FUSE mounts such as NFS, SMB or sshfs,
+/// since changes made by other machines never reach the local kernel. Such paths have
+/// to be polled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WatchBackend {
+  /// The platform's file system notifications.
+  #[default]
+  Native,
+  /// Rescans the path every `interval`.
+  ///
+  /// Files are hashed, so only changes to their contents are reported and a changed
+  /// modification time alone is not.
+  Poll { interval: Duration },
+  /// Native notifications, polling every `poll_interval` when they cannot be used for
+  /// the path.
+  ///
+  /// Polling is used if the native watcher fails, and on Linux also for paths on
+  /// network and FUSE file systems.
+  Auto { poll_interval: Duration },
+}
+
+#[derive(Clone)]
+struct TokioEventHandler {
+  tx: Sender<notify::Result<Event>>,
+}
+
+impl EventHandler for TokioEventHandler {
+  fn handle_event(&mut self, event: notify::Result<Event>) {
+    let _ = self.tx.blocking_send(event);
+  }
+}
+
+/// The watchers needed for a trigger's paths, all sending to the same channel.
+pub(crate) struct Watchers {
+  tx: Sender<notify::Result<Event>>,
+  config: Config,
+  native: Option<RecommendedWatcher>,
+  /// One poll watcher per interval.
+  polls: Vec<(Duration, PollWatcher)>,
+}
+
+impl Watchers {
+  pub(crate) fn new(tx: Sender<notify::Result<Event>>, config: Config) -> Self {
+    Self {
+      tx,
+      config,
+      native: None,
+      polls: Vec::new(),
+    }
+  }
+
+  pub(crate) fn watch(
+    &mut self,
+    path: &Path,
+    mode: RecursiveMode,
+    backend: WatchBackend,
+  ) -> Result<()> {
+    match backend {
+      WatchBackend::Native => self.native()?.watch(path, mode).map_err(Error::from),
+      WatchBackend::Poll { interval } => {
+        self.poll(interval)?.watch(path, mode).map_err(Error::from)
+      }
+      WatchBackend::Auto { poll_interval } => {
+        let native = !is_remote(path)
+          && self
+            .native()
+            .and_then(|watcher| watcher.watch(path, mode).map_err(Error::from))
+            .is_ok();
+
+        if native {
+          return Ok(());
+        }
+        self
+          .poll(poll_interval)?
+          .watch(path, mode)
+          .map_err(Error::from)
+      }
+    }
+  }
+
+  fn native(&mut self) -> Result<&mut RecommendedWatcher> {
+    if self.native.is_none() {
+      let handler = TokioEventHandler {
+        tx: self.tx.clone(),
+      };
+      self.native = Some(RecommendedWatcher::new(handler, self.config)?);
+    }
+
+    Ok(
+      self
+        .native
+        .as_mut()
+        .expect("native watcher was just created"),
+    )
+  }
+
+  fn poll(&mut self, interval: Duration) -> Result<&mut PollWatcher> {
+    let index = match self.polls.iter().position(|(i, _)| *i == interval) {
+      Some(index) => index,
+      None => {
+        let hashes = ContentHashes {
+          tx: self.tx.clone(),
+          hashes: Arc::default(),
+        };
+        let config = self.config.with_poll_interval(interval);
+        let watcher = PollWatcher::with_initial_scan(hashes.clone(), config, hashes)?;
+        self.polls.push((interval, watcher));
+        self.polls.len() - 1
+      }
+    };
+
+    Ok(&mut self.polls[index].1)
+  }
+}
+
+/// Forwards poll events, dropping modifications of files whose contents are unchanged
+/// and of directories.
+///
+/// Files are hashed when the poll watcher first scans them and again every time they
+/// appear modified.
+#[derive(Clone)]
+struct ContentHashes {
+  tx: Sender<notify::Result<Event>>,
+  hashes: Arc<Mutex<HashMap<PathBuf, u64>>>,
+}
+
+impl ContentHashes {
+  /// Records the new hashes of the event's paths, returning whether any changed.
+  fn update(&self, event: &Event) -> bool {
+    let mut hashes = self.hashes.lock();
+    match event.kind {
+      EventKind::Remove(_) => {
+        for path in &event.paths {
+          hashes.remove(path);
+        }
+        true
+      }
+      EventKind::Modify(_) => {
+        let mut changed = false;
+        // A directory's modification time changes with its entries, which are
+        // reported on their own.
+        for path in event.paths.iter().filter(|path| !path.is_dir()) {
+          let hash = hash_file(path);
+          changed |= hash.is_none() || hashes.get(path) != hash.as_ref();
+          if let Some(hash) = hash {
+            hashes.insert(path.clone(), hash);
+          }
+        }
+        changed
+      }
+      _ => {
+        for path in &event.paths {
+          if let Some(hash) = hash_file(path) {
+            hashes.insert(path.clone(), hash);
+          }
+        }
+        true
+      }
+    }
+  }
+}
+
+impl EventHandler for ContentHashes {
+  fn handle_event(&mut self, event: notify::Result<Event>) {
+    if let Ok(event) = &event
+      && !self.update(event)
+    {
+      return;
+    }
+    let _ = self.tx.blocking_send(event);
+  }
+}
+
+impl notify::poll::ScanEventHandler for ContentHashes {
+  fn handle_event(&mut self, path: notify::poll::ScanEvent) {
+    if let Ok(path) = path
+      && let Some(hash) = hash_file(&path)
+    {
+      self.hashes.lock().insert(path, hash);
+    }
+  }
+}
+
+/// Hashes a file's contents, or returns `None` for directories and unreadable files.
+fn hash_file(path: &Path) -> Option<u64> {
+  let mut file = std::fs::File::open(path).ok()?;
+  if !file.metadata().ok()?.is_file() {
+    return None;
+  }
+
+  let mut hasher = DefaultHasher::new();
+  let mut buf = [0; 64 * 1024];
+  loop {
+    match file.read(&mut buf).ok()? {
+      0 => return Some(hasher.finish()),
+      n => hasher.write(&buf[..n]),
+    }
+  }
+}
+
+/// Returns whether the path is on a network or FUSE file system, where native
+/// notifications miss changes made elsewhere.
+#[cfg(target_os = "linux")]
+fn is_remote(path: &Path) -> bool {
+  use std::os::unix::ffi::OsStrExt;
+
+  const REMOTE_MAGICS: [u32; 9] = [
+    0x6969,      // NFS
+    0x517b,      // SMB
+    0xff53_4d42, // CIFS
+    0xfe53_4d42, // SMB2
+    0x6573_5546, // FUSE, including sshfs
+    0x0102_1997, // 9P
+    0x7375_7245, // Coda
+    0x5346_414f, // AFS
+    0x00c3_6400, // Ceph
+  ];
+
+  let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+    return false;
+  };
+  // SAFETY: `statfs` is plain data, `path` is a valid C string and `stat` a valid
+  // out pointer.
+  let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+  if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+    return false;
+  }
+
+  // Magic numbers are 32-bit, while the field's width and sign vary between targets.
+  REMOTE_MAGICS.contains(&(stat.f_type as u32))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_remote(_path: &Path) -> bool {
+  false
+}
@@ -0,0 +1,177 @@
+use super::fs_event::FsEvent;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Coalesces bursts of events on the same path into one.
+///
+/// An event is held back until its path has been quiet for `delay`. Events that
+/// arrive in the meantime are merged into it, so a file written in several chunks
+/// is reported once.
+pub(crate) struct Debouncer {
+  delay: Duration,
+  pending: HashMap<PathBuf, (FsEvent, Instant)>,
+}
+
+impl Debouncer {
+  pub(crate) fn new(delay: Duration) -> Self {
+    Self {
+      delay,
+      pending: HashMap::new(),
+    }
+  }
+
+  pub(crate) fn push(&mut self, event: FsEvent) {
+    // Follow a pending event on the old name to the new one, so a file created under a
+    // temporary name and then moved into place is reported as created.
+    let event = match event {
+      FsEvent::Renamed { from, to, metadata } => match self.pending.remove(&from) {
+        Some((FsEvent::Created { .. }, _)) => FsEvent::created(to),
+        Some((FsEvent::Renamed { from, .. }, _)) => FsEvent::Renamed { from, to, metadata },
+        // Changes to the old name are superseded by the rename.
+        _ => FsEvent::Renamed { from, to, metadata },
+      },
+      event => event,
+    };
+
+    let deadline = Instant::now() + self.delay;
+    let key = event.path().to_path_buf();
+
+    let Some((pending, _)) = self.pending.remove(&key) else {
+      self.pending.insert(key, (event, deadline));
+      return;
+    };
+
+    if let Some(merged) = merge(pending, event) {
+      self.pending.insert(key, (merged, deadline));
+    }
+  }
+
+  /// When the next pending event is due, if any.
+  pub(crate) fn next_deadline(&self) -> Option<Instant> {
+    self.pending.values().map(|(_, deadline)| *deadline).min()
+  }
+
+  /// Removes and returns the events whose path has been quiet long enough, oldest first.
+  pub(crate) fn take_due(&mut self) -> Vec<FsEvent> {
+    let now = Instant::now();
+    let due: Vec<_> = self
+      .pending
+      .iter()
+      .filter(|(_, (_, deadline))| *deadline <= now)
+      .map(|(key, _)| key.clone())
+      .collect();
+
+    let mut events: Vec<_> = due
+      .into_iter()
+      .filter_map(|key| self.pending.remove(&key))
+      .collect();
+    events.sort_by_key(|(_, deadline)| *deadline);
+    events.into_iter().map(|(event, _)| event).collect()
+  }
+}
+
+/// Merges a newer event into a pending one, or returns `None` if they cancel out.
+fn merge(pending: FsEvent, next: FsEvent) -> Option<FsEvent> {
+  match (pending, next) {
+    // A file that came and went before anyone looked never existed.
+    (FsEvent::Created { .. }, FsEvent::Removed { .. }) => None,
+    // Moved and then removed, so only the old name is gone as far as anyone knows.
+    (FsEvent::Renamed { from, .. }, FsEvent::Removed { .. }) => {
+      Some(FsEvent::Removed { path: from })
+    }
+    // Writes to a new or moved file are part of creating or moving it.
+    (pending @ (FsEvent::Created { .. } | FsEvent::Renamed { .. }), FsEvent::Modified { .. }) => {
+      Some(pending)
+    }
+    // Replaced, for example by an editor saving through a temporary file.
+    (FsEvent::Removed { path }, FsEvent::Created { .. }) => Some(FsEvent::modified(path)),
+    (_, next) => Some(next),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn path(name: &str) -> PathBuf {
+    PathBuf::from("/w").join(name)
+  }
+
+  fn removed(name: &str) -> FsEvent {
+    FsEvent::Removed { path: path(name) }
+  }
+
+  fn renamed(from: &str, to: &str) -> FsEvent {
+    FsEvent::Renamed {
+      from: path(from),
+      to: path(to),
+      metadata: None,
+    }
+  }
+
+  /// Pushes the events and returns what the debouncer then reports.
+  fn debounce(events: impl IntoIterator<Item = FsEvent>) -> Vec<FsEvent> {
+    let mut debouncer = Debouncer::new(Duration::ZERO);
+    for event in events {
+      debouncer.push(event);
+    }
+    debouncer.take_due()
+  }
+
+  #[test]
+  fn merges_events_on_the_same_path() {
+    let created = FsEvent::created(path("a"));
+    let modified = FsEvent::modified(path("a"));
+
+    assert_eq!(
+      debounce([created.clone(), modified.clone()]),
+      [created.clone()]
+    );
+    assert_eq!(debounce([created, removed("a")]), []);
+    assert_eq!(
+      debounce([removed("a"), FsEvent::created(path("a"))]),
+      [modified.clone()]
+    );
+    assert_eq!(
+      debounce([modified.clone(), modified.clone()]),
+      [modified.clone()]
+    );
+    assert_eq!(debounce([modified, removed("a")]), [removed("a")]);
+  }
+
+  #[test]
+  fn merges_into_renames() {
+    assert_eq!(
+      debounce([renamed("a", "b"), FsEvent::modified(path("b"))]),
+      [renamed("a", "b")]
+    );
+    assert_eq!(debounce([renamed("a", "b"), removed("b")]), [removed("a")]);
+  }
+
+  #[test]
+  fn follows_pending_events_to_the_new_name() {
+    // Written under a temporary name and moved into place.
+    assert_eq!(
+      debounce([FsEvent::created(path("a.tmp")), renamed("a.tmp", "a")]),
+      [FsEvent::created(path("a"))]
+    );
+    assert_eq!(
+      debounce([renamed("a", "b"), renamed("b", "c")]),
+      [renamed("a", "c")]
+    );
+    assert_eq!(
+      debounce([FsEvent::modified(path("a")), renamed("a", "b")]),
+      [renamed("a", "b")]
+    );
+  }
+
+  #[test]
+  fn holds_events_until_the_path_is_quiet() {
+    let mut debouncer = Debouncer::new(Duration::from_secs(60));
+    debouncer.push(FsEvent::created(path("a")));
+    assert!(debouncer.take_due().is_empty());
+    assert!(debouncer.next_deadline().is_some());
+  }
+}
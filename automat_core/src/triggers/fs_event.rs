@@ -0,0 +1,343 @@
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+
+/// How long the old name of a renamed file waits for its new name when no other event
+/// arrives. Unpaired old names were moved out of the watched paths and are reported as
+/// removed.
+const RENAME_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// A change to a watched path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEvent {
+  Created {
+    path: PathBuf,
+    metadata: Option<FsMetadata>,
+  },
+  Modified {
+    path: PathBuf,
+    metadata: Option<FsMetadata>,
+  },
+  Removed {
+    path: PathBuf,
+  },
+  /// A path moved within the watched paths. Paths moved in or out of them are
+  /// reported as created or removed.
+  Renamed {
+    from: PathBuf,
+    to: PathBuf,
+    metadata: Option<FsMetadata>,
+  },
+}
+
+/// Details of a path, read just before its event is delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsMetadata {
+  /// Size in bytes.
+  pub size: u64,
+  /// Last modification time, if the platform records it.
+  pub modified: Option<SystemTime>,
+  pub is_dir: bool,
+}
+
+impl From<std::fs::Metadata> for FsMetadata {
+  fn from(metadata: std::fs::Metadata) -> Self {
+    Self {
+      size: metadata.len(),
+      modified: metadata.modified().ok(),
+      is_dir: metadata.is_dir(),
+    }
+  }
+}
+
+/// Kinds of changes a [`FileSystemTrigger`](crate::FileSystemTrigger) can be limited to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FsEventKind {
+  Create,
+  Modify,
+  Remove,
+  Rename,
+}
+
+impl FsEvent {
+  /// The changed path, or the new path of a rename.
+  pub fn path(&self) -> &Path {
+    match self {
+      Self::Created { path, .. } | Self::Modified { path, .. } | Self::Removed { path } => path,
+      Self::Renamed { to, .. } => to,
+    }
+  }
+
+  /// Every path the event is about, including the old path of a rename.
+  pub fn paths(&self) -> impl Iterator<Item = &Path> {
+    let from = match self {
+      Self::Renamed { from, .. } => Some(from.as_path()),
+      _ => None,
+    };
+    from.into_iter().chain(std::iter::once(self.path()))
+  }
+
+  pub fn kind(&self) -> FsEventKind {
+    match self {
+      Self::Created { .. } => FsEventKind::Create,
+      Self::Modified { .. } => FsEventKind::Modify,
+      Self::Removed { .. } => FsEventKind::Remove,
+      Self::Renamed { .. } => FsEventKind::Rename,
+    }
+  }
+
+  /// Metadata of the path, unless it was removed or could not be read.
+  pub fn metadata(&self) -> Option<&FsMetadata> {
+    match self {
+      Self::Created { metadata, .. }
+      | Self::Modified { metadata, .. }
+      | Self::Renamed { metadata, .. } => metadata.as_ref(),
+      Self::Removed { .. } => None,
+    }
+  }
+
+  pub(crate) fn load_metadata(&mut self) {
+    let loaded = std::fs::metadata(self.path()).ok().map(FsMetadata::from);
+    match self {
+      Self::Created { metadata, .. }
+      | Self::Modified { metadata, .. }
+      | Self::Renamed { metadata, .. } => *metadata = loaded,
+      Self::Removed { .. } => {}
+    }
+  }
+
+  pub(crate) fn created(path: PathBuf) -> Self {
+    Self::Created {
+      path,
+      metadata: None,
+    }
+  }
+
+  pub(crate) fn modified(path: PathBuf) -> Self {
+    Self::Modified {
+      path,
+      metadata: None,
+    }
+  }
+}
+
+/// Turns raw `notify` events into [`FsEvent`]s, pairing the two halves of renames.
+///
+/// Backends report renames differently: inotify sends the old and new name with a
+/// shared cookie followed by a combined event, Windows sends the two names back to
+/// back, and FSEvents and kqueue only say that a name changed. Halves without a
+/// cookie are told apart by whether their path still exists.
+#[derive(Default)]
+pub(crate) struct Renames {
+  /// Old name waiting for its new name, with its cookie.
+  pending: Option<(PathBuf, Option<usize>, Instant)>,
+  /// Cookie of the last pair, whose combined event is then redundant.
+  paired: Option<usize>,
+}
+
+impl Renames {
+  pub(crate) fn push(&mut self, event: Event) -> Vec<FsEvent> {
+    let tracker = event.tracker();
+    let mut events = Vec::new();
+
+    // Both halves are always reported back to back, so anything else in between means
+    // the old name was moved out.
+    if !matches!(event.kind, EventKind::Modify(ModifyKind::Name(_))) {
+      events.extend(self.take_pending());
+    }
+
+    match event.kind {
+      EventKind::Create(_) => events.extend(event.paths.into_iter().map(FsEvent::created)),
+      EventKind::Remove(_) => {
+        events.extend(
+          event
+            .paths
+            .into_iter()
+            .map(|path| FsEvent::Removed { path }),
+        );
+      }
+      EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+        if tracker.is_some() && self.paired == tracker {
+          return events;
+        }
+        let mut paths = event.paths.into_iter();
+        if let (Some(from), Some(to)) = (paths.next(), paths.next()) {
+          events.push(FsEvent::Renamed {
+            from,
+            to,
+            metadata: None,
+          });
+        }
+      }
+      EventKind::Modify(ModifyKind::Name(mode)) => {
+        for path in event.paths {
+          let is_old_name = match mode {
+            RenameMode::From => true,
+            RenameMode::To => false,
+            _ => !path.exists(),
+          };
+
+          if is_old_name {
+            events.extend(self.take_pending());
+            self.pending = Some((path, tracker, Instant::now() + RENAME_TIMEOUT));
+          } else {
+            self.new_name(path, tracker, &mut events);
+          }
+        }
+      }
+      EventKind::Modify(_) | EventKind::Any => {
+        events.extend(event.paths.into_iter().map(FsEvent::modified));
+      }
+      // Reads, and events about the watcher itself such as a rescan.
+      EventKind::Access(_) | EventKind::Other => {}
+    }
+
+    events
+  }
+
+  /// When the pending old name should be given up on, if there is one.
+  pub(crate) fn next_deadline(&self) -> Option<Instant> {
+    self.pending.as_ref().map(|(_, _, deadline)| *deadline)
+  }
+
+  /// Reports the pending old name as removed if its new name did not arrive in time.
+  pub(crate) fn take_expired(&mut self) -> Option<FsEvent> {
+    if self.next_deadline()? > Instant::now() {
+      return None;
+    }
+    self.take_pending()
+  }
+
+  fn take_pending(&mut self) -> Option<FsEvent> {
+    self
+      .pending
+      .take()
+      .map(|(path, _, _)| FsEvent::Removed { path })
+  }
+
+  fn new_name(&mut self, to: PathBuf, tracker: Option<usize>, events: &mut Vec<FsEvent>) {
+    match self.pending.take() {
+      Some((from, pending_tracker, _)) if pending_tracker == tracker => {
+        self.paired = tracker;
+        events.push(FsEvent::Renamed {
+          from,
+          to,
+          metadata: None,
+        });
+      }
+      pending => {
+        // Neither name has a counterpart: one left and another came in from outside
+        // the watched paths.
+        events.extend(pending.map(|(path, _, _)| FsEvent::Removed { path }));
+        events.push(FsEvent::created(to));
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use notify::event::{CreateKind, DataChange};
+
+  fn rename(mode: RenameMode, path: &str, cookie: Option<usize>) -> Event {
+    let event = Event::new(EventKind::Modify(ModifyKind::Name(mode))).add_path(path.into());
+    match cookie {
+      Some(cookie) => event.set_tracker(cookie),
+      None => event,
+    }
+  }
+
+  fn renamed(from: &str, to: &str) -> FsEvent {
+    FsEvent::Renamed {
+      from: from.into(),
+      to: to.into(),
+      metadata: None,
+    }
+  }
+
+  #[test]
+  fn pairs_inotify_halves_and_skips_the_combined_event() {
+    let mut renames = Renames::default();
+    assert_eq!(renames.push(rename(RenameMode::From, "/w/a", Some(7))), []);
+    assert_eq!(
+      renames.push(rename(RenameMode::To, "/w/b", Some(7))),
+      [renamed("/w/a", "/w/b")]
+    );
+
+    let both = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+      .add_path("/w/a".into())
+      .add_path("/w/b".into())
+      .set_tracker(7);
+    assert_eq!(renames.push(both), []);
+  }
+
+  #[test]
+  fn pairs_halves_without_cookies() {
+    let mut renames = Renames::default();
+    assert_eq!(renames.push(rename(RenameMode::From, "/w/a", None)), []);
+    assert_eq!(
+      renames.push(rename(RenameMode::To, "/w/b", None)),
+      [renamed("/w/a", "/w/b")]
+    );
+  }
+
+  #[test]
+  fn reports_unpaired_halves_as_removed_and_created() {
+    let mut renames = Renames::default();
+    renames.push(rename(RenameMode::From, "/w/a", Some(1)));
+    assert_eq!(
+      renames.push(rename(RenameMode::To, "/w/b", Some(2))),
+      [
+        FsEvent::Removed {
+          path: "/w/a".into()
+        },
+        FsEvent::created("/w/b".into())
+      ]
+    );
+
+    // Any other event means the old name was moved out of the watched paths.
+    renames.push(rename(RenameMode::From, "/w/c", Some(3)));
+    let create = Event::new(EventKind::Create(CreateKind::File)).add_path("/w/d".into());
+    assert_eq!(
+      renames.push(create),
+      [
+        FsEvent::Removed {
+          path: "/w/c".into()
+        },
+        FsEvent::created("/w/d".into())
+      ]
+    );
+    assert_eq!(renames.next_deadline(), None);
+  }
+
+  #[test]
+  fn expires_the_old_name() {
+    let mut renames = Renames::default();
+    renames.push(rename(RenameMode::From, "/w/a", Some(1)));
+    assert_eq!(renames.take_expired(), None);
+
+    std::thread::sleep(RENAME_TIMEOUT);
+    assert_eq!(
+      renames.take_expired(),
+      Some(FsEvent::Removed {
+        path: "/w/a".into()
+      })
+    );
+    assert_eq!(renames.next_deadline(), None);
+  }
+
+  #[test]
+  fn maps_other_kinds() {
+    let mut renames = Renames::default();
+    let write =
+      Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content))).add_path("/w/a".into());
+    assert_eq!(renames.push(write), [FsEvent::modified("/w/a".into())]);
+
+    let read =
+      Event::new(EventKind::Access(notify::event::AccessKind::Any)).add_path("/w/a".into());
+    assert_eq!(renames.push(read), []);
+  }
+}
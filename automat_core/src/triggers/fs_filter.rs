@@ -0,0 +1,198 @@
+use super::fs_event::{FsEvent, FsEventKind};
+use crate::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Filters configured on a file system trigger, compiled when it starts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FilterOptions {
+  pub(crate) include: Vec<String>,
+  pub(crate) exclude: Vec<String>,
+  /// Only these kinds pass when set.
+  pub(crate) kinds: Option<Vec<FsEventKind>>,
+  pub(crate) gitignore: bool,
+}
+
+impl FilterOptions {
+  /// Adds a glob pattern, excluding matches if it starts with `!`.
+  pub(crate) fn add_glob(&mut self, pattern: &str) {
+    match pattern.strip_prefix('!') {
+      Some(pattern) => self.exclude.push(pattern.to_string()),
+      None => self.include.push(pattern.to_string()),
+    }
+  }
+}
+
+/// Drops events and paths a trigger is not interested in.
+pub(crate) struct EventFilter {
+  include: Option<GlobSet>,
+  exclude: Option<GlobSet>,
+  kinds: Option<Vec<FsEventKind>>,
+  gitignore: Option<GitignoreCache>,
+  /// Watched paths, used to match globs against paths relative to them.
+  roots: Vec<PathBuf>,
+}
+
+impl EventFilter {
+  pub(crate) fn new(options: &FilterOptions, roots: &[PathBuf]) -> Result<Self> {
+    Ok(Self {
+      include: glob_set(&options.include)?,
+      exclude: glob_set(&options.exclude)?,
+      kinds: options.kinds.clone(),
+      gitignore: options.gitignore.then(GitignoreCache::default),
+      roots: roots.iter().map(|root| absolute(root)).collect(),
+    })
+  }
+
+  /// Returns whether the event should be reported. Renames are reported if either
+  /// name passes.
+  pub(crate) fn accepts(&mut self, event: &FsEvent) -> bool {
+    if let Some(kinds) = &self.kinds
+      && !kinds.contains(&event.kind())
+    {
+      return false;
+    }
+
+    if let Some(gitignore) = &mut self.gitignore {
+      gitignore.invalidate(event.paths());
+    }
+
+    event.paths().any(|path| self.accepts_path(path))
+  }
+
+  fn accepts_path(&mut self, path: &Path) -> bool {
+    let path = absolute(path);
+    let relative = self
+      .roots
+      .iter()
+      .find_map(|root| path.strip_prefix(root).ok())
+      .unwrap_or(&path);
+    let matches = |set: &GlobSet| set.is_match(relative) || set.is_match(&path);
+
+    if self.include.as_ref().is_some_and(|set| !matches(set)) {
+      return false;
+    }
+    if self.exclude.as_ref().is_some_and(matches) {
+      return false;
+    }
+
+    !self
+      .gitignore
+      .as_mut()
+      .is_some_and(|gitignore| gitignore.is_ignored(&path))
+  }
+}
+
+fn glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+  if patterns.is_empty() {
+    return Ok(None);
+  }
+
+  let mut builder = GlobSetBuilder::new();
+  for pattern in patterns {
+    let glob =
+      Glob::new(pattern).map_err(|e| Error::InvalidGlob(format!("`{}`: {}", pattern, e.kind())))?;
+    builder.add(glob);
+  }
+
+  builder
+    .build()
+    .map(Some)
+    .map_err(|e| Error::InvalidGlob(e.to_string()))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+  std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `.gitignore` matchers of the directories events were seen in.
+///
+/// Like git, ignore files only apply inside a repository. Each directory from the
+/// changed path up to the repository root may have its own `.gitignore`, with deeper
+/// files taking precedence, and the root also contributes `.git/info/exclude`.
+#[derive(Default)]
+struct GitignoreCache {
+  directories: HashMap<PathBuf, Directory>,
+  global: Option<Gitignore>,
+}
+
+struct Directory {
+  matcher: Gitignore,
+  is_repository_root: bool,
+}
+
+impl GitignoreCache {
+  fn is_ignored(&mut self, path: &Path) -> bool {
+    // Git's own bookkeeping is never interesting.
+    if path
+      .components()
+      .any(|component| component.as_os_str() == ".git")
+    {
+      return true;
+    }
+
+    let is_dir = path.is_dir();
+    let mut matches = Vec::new();
+    let mut inside_repository = false;
+
+    for dir in path.ancestors().skip(1) {
+      let directory = self.directory(dir);
+      matches.push(
+        match directory.matcher.matched_path_or_any_parents(path, is_dir) {
+          Match::None => None,
+          Match::Ignore(_) => Some(true),
+          Match::Whitelist(_) => Some(false),
+        },
+      );
+
+      if directory.is_repository_root {
+        inside_repository = true;
+        break;
+      }
+    }
+
+    if !inside_repository {
+      return false;
+    }
+
+    // Ancestors were visited from the deepest one, which takes precedence.
+    matches.into_iter().flatten().next().unwrap_or_else(|| {
+      let global = self.global.get_or_insert_with(|| Gitignore::global().0);
+      global.matched(path, is_dir).is_ignore()
+    })
+  }
+
+  fn directory(&mut self, dir: &Path) -> &Directory {
+    self
+      .directories
+      .entry(dir.to_path_buf())
+      .or_insert_with(|| {
+        let is_repository_root = dir.join(".git").exists();
+        let mut builder = GitignoreBuilder::new(dir);
+        builder.add(dir.join(".gitignore"));
+        if is_repository_root {
+          builder.add(dir.join(".git/info/exclude"));
+        }
+
+        Directory {
+          // Unreadable or invalid ignore files ignore nothing.
+          matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+          is_repository_root,
+        }
+      })
+  }
+
+  /// Forgets the matchers of directories whose `.gitignore` changed.
+  fn invalidate<'a>(&mut self, paths: impl Iterator<Item = &'a Path>) {
+    for path in paths {
+      if path.file_name().is_some_and(|name| name == ".gitignore")
+        && let Some(dir) = path.parent()
+      {
+        self.directories.remove(&absolute(dir));
+      }
+    }
+  }
+}
@@ -1,30 +1,30 @@
+use super::fs_backend::Watchers;
+use super::fs_debounce::Debouncer;
+use super::fs_event::Renames;
+use super::fs_filter::{EventFilter, FilterOptions};
 use crate::{
-  callback, impl_display_debug, pair_api, send_error, Error, Result, Trigger,
-  TriggerContext, TriggerEvent, TriggerRuntime,
+  callback, impl_display_debug, pair_api, send_error, Error, FsEvent, FsEventKind, Result,
+  Trigger, TriggerContext, TriggerEvent, TriggerRuntime, WatchBackend,
 };
 use async_trait::async_trait;
-use notify::{Config, Event, EventHandler, RecursiveMode, Watcher};
+use notify::{Config, Event, RecursiveMode};
 use std::path::PathBuf;
-use tokio::sync::mpsc::Sender;
-
-#[derive(Clone)]
-struct TokioEventHandler {
-  tx: Sender<notify::Result<Event>>,
-}
-
-impl EventHandler for TokioEventHandler {
-  fn handle_event(&mut self, event: notify::Result<Event>) {
-    let _ = self.tx.blocking_send(event);
-  }
-}
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant};
 
 callback!(FileSystemCallback<T>);
 
 /// A trigger that watches for file system events and executes a callback when events occur.
+///
+/// Errors from the watcher are reported to the runner instead of the callback.
 pub struct FileSystemTrigger {
-  callback: FileSystemCallback<TriggerContext<Result<Event>>>,
+  callback: FileSystemCallback<TriggerContext<FsEvent>>,
   config: Option<Config>,
-  watch_paths: Vec<(PathBuf, RecursiveMode)>,
+  /// Paths with the backend chosen for them, if any.
+  watch_paths: Vec<(PathBuf, RecursiveMode, Option<WatchBackend>)>,
+  backend: WatchBackend,
+  debounce: Option<Duration>,
+  filter: FilterOptions,
 }
 
 impl FileSystemTrigger {
@@ -32,10 +32,21 @@ impl FileSystemTrigger {
     assoc
       /// Creates a new `FileSystemTrigger` with the given callback.
       new(f: F)
-        callback(TriggerContext<Result<Event>>)
-        async => Self { callback: new_file_system_callback(f), config: None, watch_paths: Vec::new() };
+        callback(TriggerContext<FsEvent>)
+        async => Self::with_callback(new_file_system_callback(f));
         /// Creates a new `FileSystemTrigger` with a synchronous (blocking) callback.
-        blocking => Self { callback: new_file_system_callback_blocking(f), config: None, watch_paths: Vec::new() };
+        blocking => Self::with_callback(new_file_system_callback_blocking(f));
+  }
+
+  fn with_callback(callback: FileSystemCallback<TriggerContext<FsEvent>>) -> Self {
+    Self {
+      callback,
+      config: None,
+      watch_paths: Vec::new(),
+      backend: WatchBackend::default(),
+      debounce: None,
+      filter: FilterOptions::default(),
+    }
   }
 
   /// Configures the watcher with custom settings.
@@ -45,7 +56,27 @@ impl FileSystemTrigger {
   }
 
   /// Adds a path to be monitored for file system events.
-  pub fn watch_path(mut self, path: PathBuf, recursive: bool) -> Self {
+  pub fn watch_path(self, path: PathBuf, recursive: bool) -> Self {
+    self.add_watch_path(path, recursive, None)
+  }
+
+  /// Adds a path to be monitored with the given backend instead of the trigger's
+  /// [default one](Self::with_backend).
+  pub fn watch_path_with_backend(
+    self,
+    path: PathBuf,
+    recursive: bool,
+    backend: WatchBackend,
+  ) -> Self {
+    self.add_watch_path(path, recursive, Some(backend))
+  }
+
+  fn add_watch_path(
+    mut self,
+    path: PathBuf,
+    recursive: bool,
+    backend: Option<WatchBackend>,
+  ) -> Self {
     self.watch_paths.push((
       path,
       if recursive {
@@ -53,13 +84,84 @@ impl FileSystemTrigger {
       } else {
         RecursiveMode::NonRecursive
       },
+      backend,
     ));
     self
   }
 
+  /// Sets how paths without a backend of their own are watched. Defaults to
+  /// [`WatchBackend::Native`].
+  pub fn with_backend(mut self, backend: WatchBackend) -> Self {
+    self.backend = backend;
+    self
+  }
+
+  /// Waits until the paths of an event have been quiet for `delay` before reporting it.
+  ///
+  /// Events on the same paths in the meantime are merged into one: a created file that
+  /// is then written is reported as created, and one that is removed again is not
+  /// reported at all.
+  pub fn with_debounce(mut self, delay: Duration) -> Self {
+    self.debounce = Some(delay);
+    self
+  }
+
+  /// Only reports paths matching the glob, such as `*.rs` or `src/**`.
+  ///
+  /// Globs are matched against paths relative to the watched path, and against the
+  /// absolute path. A pattern starting with `!` excludes matches instead, like
+  /// [`exclude`](Self::exclude).
+  pub fn include(mut self, pattern: &str) -> Self {
+    self.filter.add_glob(pattern);
+    self
+  }
+
+  /// Never reports paths matching the glob, such as `target/**`.
+  ///
+  /// Exclusions take precedence over [`include`](Self::include).
+  pub fn exclude(mut self, pattern: &str) -> Self {
+    self.filter.exclude.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports events of the given kinds. Access events are never reported once
+  /// this is set.
+  pub fn with_event_kinds(mut self, kinds: impl IntoIterator<Item = FsEventKind>) -> Self {
+    self.filter.kinds = Some(kinds.into_iter().collect());
+    self
+  }
+
+  /// Skips paths ignored by git, using the `.gitignore` files of the repository they
+  /// are in, `.git/info/exclude` and the global excludes file.
+  ///
+  /// Changes inside `.git` are skipped too. Paths outside a repository are unaffected.
+  pub fn respect_gitignore(mut self, respect: bool) -> Self {
+    self.filter.gitignore = respect;
+    self
+  }
+
+  /// Checks a glob pattern without creating a trigger.
+  ///
+  /// Accepts the same patterns as [`include`](Self::include), including a leading `!`.
+  pub fn validate_glob(pattern: &str) -> Result<()> {
+    let mut options = FilterOptions::default();
+    options.add_glob(pattern);
+    EventFilter::new(&options, &[]).map(|_| ())
+  }
+
   pub fn watch_count(&self) -> usize {
     self.watch_paths.len()
   }
+
+  /// Runs the callback, returning whether the trigger should keep going.
+  async fn deliver(&self, mut event: FsEvent, rt: &TriggerRuntime) -> bool {
+    event.load_metadata();
+    let ctx = TriggerContext::new(event, rt.tx.clone());
+    match (self.callback)(ctx).await {
+      Ok(()) => true,
+      Err(err) => send_error(&rt.tx, err, "FileSystemTrigger").await,
+    }
+  }
 }
 
 #[async_trait]
@@ -68,8 +170,6 @@ impl Trigger for FileSystemTrigger {
   ///
   /// This method blocks until an error occurs or the watcher is stopped.
   async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
-    use notify::RecommendedWatcher;
-
     if self.watch_paths.is_empty() {
       let _ = rt
         .tx
@@ -79,41 +179,68 @@ impl Trigger for FileSystemTrigger {
       return Ok(());
     }
     let (fs_tx, mut fs_rx) = tokio::sync::mpsc::channel::<notify::Result<Event>>(1024);
-    let handler = TokioEventHandler { tx: fs_tx };
-
-    let mut watcher =
-      match RecommendedWatcher::new(handler, self.config.clone().unwrap_or_default()) {
-        Ok(w) => w,
-        Err(e) => {
-          send_error(&rt.tx, Error::from(e), "FileSystemTrigger").await;
+    let mut watchers = Watchers::new(fs_tx, self.config.unwrap_or_default());
 
-          return Ok(());
-        }
-      };
-
-    for (path, mode) in &self.watch_paths {
-      if let Err(e) = watcher.watch(path, *mode) {
-        if !send_error(&rt.tx, Error::from(e), "FileSystemTrigger").await {
-          return Ok(());
-        }
+    for (path, mode, backend) in &self.watch_paths {
+      if let Err(e) = watchers.watch(path, *mode, backend.unwrap_or(self.backend))
+        && !send_error(&rt.tx, e, "FileSystemTrigger").await
+      {
+        return Ok(());
       }
     }
 
+    let roots: Vec<_> = self.watch_paths.iter().map(|(path, _, _)| path.clone()).collect();
+    let mut filter = match EventFilter::new(&self.filter, &roots) {
+      Ok(filter) => filter,
+      Err(e) => {
+        let _ = rt.tx.send(TriggerEvent::ErrorFatal(e)).await;
+        return Ok(());
+      }
+    };
+    let mut renames = Renames::default();
+    let mut debouncer = self.debounce.map(Debouncer::new);
+
     loop {
-      tokio::select! {
+      let deadline = renames
+        .next_deadline()
+        .into_iter()
+        .chain(debouncer.as_ref().and_then(Debouncer::next_deadline))
+        .min();
+
+      let (events, mut due) = tokio::select! {
         _ = rt.shutdown.cancelled() => break,
+        () = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => (
+          renames.take_expired().into_iter().collect(),
+          debouncer.as_mut().map(Debouncer::take_due).unwrap_or_default(),
+        ),
         maybe = fs_rx.recv() => {
-          let Some(res) = maybe else {
-            return Err(Error::FileWatcherStopped);
-          };
-
-          let ctx=  TriggerContext::new(res.map_err(Into::into), rt.tx.clone());
-          if let Err(err) = (self.callback)(ctx).await {
-            if !send_error(&rt.tx, err, "FileSystemTrigger").await {
-              break;
+          match maybe {
+            Some(Ok(event)) => (renames.push(event), Vec::new()),
+            Some(Err(e)) => {
+              if !send_error(&rt.tx, Error::from(e), "FileSystemTrigger").await {
+                break;
+              }
+              continue;
             }
+            None => return Err(Error::FileWatcherStopped),
           }
         }
+      };
+
+      for event in events {
+        if !filter.accepts(&event) {
+          continue;
+        }
+        match &mut debouncer {
+          Some(debouncer) => debouncer.push(event),
+          None => due.push(event),
+        }
+      }
+
+      for event in due {
+        if !self.deliver(event, &rt).await {
+          return Ok(());
+        }
       }
     }
 
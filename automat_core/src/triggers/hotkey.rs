@@ -0,0 +1,252 @@
+use crate::{
+  Accelerator, Error, Result, Trigger, TriggerContext, TriggerRuntime, callback, pair_api,
+};
+use async_trait::async_trait;
+use derivative::Derivative;
+
+callback!(HotkeyCallback<T>);
+
+/// HotkeyTrigger fires when a global key combination is pressed.
+///
+/// The combination is grabbed for the whole session, so other applications no longer
+/// receive it while the trigger runs. See [`Accelerator`] for the accepted syntax.
+/// Holding the combination fires once; the trigger fires again after the key is released.
+///
+/// Only supported on Linux with an X11 session. Starting the trigger fails with
+/// [`Error::HotkeyConflict`] if another client already grabbed the combination.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let trigger = HotkeyTrigger::new_blocking("Ctrl+Alt+T", |ctx| {
+///   println!("{} pressed", ctx.data);
+///   Ok(())
+/// });
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct HotkeyTrigger {
+  source: String,
+  accelerator: std::result::Result<Accelerator, String>,
+  #[derivative(Debug = "ignore")]
+  callback: HotkeyCallback<TriggerContext<Accelerator>>,
+}
+
+impl HotkeyTrigger {
+  pair_api! {
+    assoc
+      new(accelerator: &str, f: F)
+        callback(TriggerContext<Accelerator>)
+        async => Self::with_callback(accelerator, new_hotkey_callback(f));
+        blocking => Self::with_callback(accelerator, new_hotkey_callback_blocking(f));
+  }
+
+  pair_api! {
+    assoc
+      /// Creates a trigger for an already parsed key combination.
+      from_accelerator(accelerator: Accelerator, f: F)
+        callback(TriggerContext<Accelerator>)
+        async => Self::with_accelerator(accelerator, new_hotkey_callback(f));
+        blocking => Self::with_accelerator(accelerator, new_hotkey_callback_blocking(f));
+  }
+
+  fn with_callback(
+    accelerator: &str,
+    callback: HotkeyCallback<TriggerContext<Accelerator>>,
+  ) -> Self {
+    Self {
+      source: accelerator.to_string(),
+      accelerator: Accelerator::parse(accelerator).map_err(|err| match err {
+        Error::InvalidAccelerator(msg) => msg,
+        other => other.to_string(),
+      }),
+      callback,
+    }
+  }
+
+  fn with_accelerator(
+    accelerator: Accelerator,
+    callback: HotkeyCallback<TriggerContext<Accelerator>>,
+  ) -> Self {
+    Self {
+      source: accelerator.to_string(),
+      accelerator: Ok(accelerator),
+      callback,
+    }
+  }
+
+  /// Returns the parsed key combination, or the parse error message.
+  pub fn accelerator(&self) -> std::result::Result<&Accelerator, &str> {
+    self.accelerator.as_ref().map_err(String::as_str)
+  }
+}
+
+#[async_trait]
+impl Trigger for HotkeyTrigger {
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    let accelerator = self
+      .accelerator
+      .clone()
+      .map_err(Error::InvalidAccelerator)?;
+
+    listen(&accelerator, &self.callback, &rt).await
+  }
+
+  fn name(&self) -> String {
+    format!("HotkeyTrigger ({})", self.source)
+  }
+}
+
+#[cfg(target_os = "linux")]
+use linux::listen;
+
+#[cfg(not(target_os = "linux"))]
+async fn listen(
+  _: &Accelerator,
+  _: &HotkeyCallback<TriggerContext<Accelerator>>,
+  _: &TriggerRuntime,
+) -> Result<()> {
+  Err(Error::HotkeyError(
+    "global hotkeys are only supported on X11".to_string(),
+  ))
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+  use super::HotkeyCallback;
+  use crate::linux::XEventConnection;
+  use crate::{Accelerator, Error, Key, Result, TriggerContext, TriggerRuntime, send_err};
+  use std::ptr;
+  use x11::xlib::{
+    BadAccess, ControlMask, GrabModeAsync, KeyPress, KeyRelease, LockMask, Mod1Mask, Mod2Mask,
+    Mod4Mask, ShiftMask, XGrabKey, XKeysymToKeycode, XSync, XUngrabKey, XkbSetDetectableAutoRepeat,
+  };
+
+  /// Lock modifiers that should not prevent a hotkey from matching (Caps Lock, Num Lock).
+  const IGNORED_MASKS: [u32; 4] = [0, LockMask, Mod2Mask, LockMask | Mod2Mask];
+
+  /// An active key grab, released when dropped.
+  struct KeyGrab<'a> {
+    conn: &'a XEventConnection,
+    keycode: i32,
+    modifiers: u32,
+  }
+
+  impl<'a> KeyGrab<'a> {
+    fn new(conn: &'a XEventConnection, accelerator: &Accelerator) -> Result<Self> {
+      let keysym = xkeysym::Keysym::from(accelerator.key()).raw();
+      let keycode = unsafe { XKeysymToKeycode(conn.display(), keysym.into()) };
+      if keycode == 0 {
+        return Err(Error::HotkeyError(format!(
+          "{} has no key on the current keyboard layout",
+          accelerator
+        )));
+      }
+
+      let grab = Self {
+        conn,
+        keycode: keycode.into(),
+        modifiers: accelerator
+          .modifiers()
+          .iter()
+          .map(|key| modifier_mask(*key))
+          .fold(0, |mask, m| mask | m),
+      };
+
+      let root = conn.root();
+      let (_, error) = conn.trap_errors(|display| {
+        for ignored in IGNORED_MASKS {
+          unsafe {
+            XGrabKey(
+              display,
+              grab.keycode,
+              grab.modifiers | ignored,
+              root,
+              0,
+              GrabModeAsync,
+              GrabModeAsync,
+            );
+          }
+        }
+      });
+
+      // Dropping `grab` releases the combinations that were grabbed before a conflict.
+      match error {
+        None => Ok(grab),
+        Some(code) if code == BadAccess => Err(Error::HotkeyConflict(accelerator.to_string())),
+        Some(code) => Err(Error::HotkeyError(format!(
+          "failed to grab {} (X error {})",
+          accelerator, code
+        ))),
+      }
+    }
+
+    fn matches(&self, keycode: u32, state: u32) -> bool {
+      let relevant = ControlMask | ShiftMask | Mod1Mask | Mod4Mask;
+      keycode as i32 == self.keycode && state & relevant == self.modifiers
+    }
+  }
+
+  impl Drop for KeyGrab<'_> {
+    fn drop(&mut self) {
+      let root = self.conn.root();
+      unsafe {
+        for ignored in IGNORED_MASKS {
+          XUngrabKey(
+            self.conn.display(),
+            self.keycode,
+            self.modifiers | ignored,
+            root,
+          );
+        }
+        XSync(self.conn.display(), 0);
+      }
+    }
+  }
+
+  fn modifier_mask(key: Key) -> u32 {
+    match key {
+      Key::Control => ControlMask,
+      Key::Alt => Mod1Mask,
+      Key::Shift => ShiftMask,
+      Key::Meta => Mod4Mask,
+      _ => 0,
+    }
+  }
+
+  pub(super) async fn listen(
+    accelerator: &Accelerator,
+    callback: &HotkeyCallback<TriggerContext<Accelerator>>,
+    rt: &TriggerRuntime,
+  ) -> Result<()> {
+    let conn = XEventConnection::open()?;
+    // Without detectable auto-repeat, holding a key sends release/press pairs.
+    unsafe { XkbSetDetectableAutoRepeat(conn.display(), 1, ptr::null_mut()) };
+
+    let grab = KeyGrab::new(&conn, accelerator)?;
+    let mut held = false;
+
+    loop {
+      // Only plain fields are kept; the event itself holds a display pointer.
+      let (kind, keycode, state) = tokio::select! {
+        _ = rt.shutdown.cancelled() => break,
+        event = conn.next_event() => {
+          let event = event?;
+          let key = unsafe { event.key };
+          (event.get_type(), key.keycode, key.state)
+        }
+      };
+
+      if kind == KeyPress && !held && grab.matches(keycode, state) {
+        held = true;
+        let ctx = TriggerContext::new(accelerator.clone(), rt.tx.clone());
+        send_err!(callback(ctx).await, "HotkeyTrigger", &rt.tx, break);
+      } else if kind == KeyRelease && keycode as i32 == grab.keycode {
+        held = false;
+      }
+    }
+
+    drop(grab);
+    Ok(())
+  }
+}
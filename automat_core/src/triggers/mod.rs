@@ -1,21 +1,36 @@
 mod clipboard;
 mod context;
+mod fs_backend;
+mod fs_debounce;
+mod fs_event;
+mod fs_filter;
 mod fs_watcher;
+mod hotkey;
 mod interval;
 mod process;
+mod process_filter;
+mod resource;
+mod schedule;
 mod window;
+mod window_event;
 
 use super::error::{Error, Result};
 use async_trait::async_trait;
 pub use clipboard::*;
 pub use context::*;
+pub use fs_backend::WatchBackend;
+pub use fs_event::{FsEvent, FsEventKind, FsMetadata};
 pub use fs_watcher::*;
+pub use hotkey::*;
 pub use interval::*;
 pub use process::*;
+pub use resource::*;
+pub use schedule::*;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio_util::sync::CancellationToken;
 pub use window::*;
+pub use window_event::*;
 
 /// Error handler for trigger callbacks.
 pub type TriggerErrorHandler = Arc<dyn Fn(Error) + Send + Sync>;
@@ -1,22 +1,74 @@
+use super::process_filter::{ProcessFilter, ProcessFilterOptions};
 use crate::triggers::context::{TriggerContext, TriggerEvent};
-use crate::{callback, pair_api, send_err, Result, Trigger, TriggerRuntime};
+use crate::{Result, Trigger, TriggerRuntime, callback, pair_api, send_err};
 use async_trait::async_trait;
+use derivative::Derivative;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use std::collections::HashMap;
-use std::time::Duration;
-use derivative::Derivative;
-use sysinfo::{ProcessesToUpdate, System};
-use tokio::sync::mpsc::Sender;
+use std::collections::hash_map::Entry;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{
+  Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, Uid, UpdateKind, Users,
+};
 use tokio::time::sleep;
 
 static SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
 
+static USERS: Lazy<Mutex<Users>> = Lazy::new(|| Mutex::new(Users::new_with_refreshed_list()));
+
 /// Information about a process.
 #[derive(Clone, Debug)]
 pub struct ProcessInfo {
   pub pid: u32,
   pub name: String,
+  /// Path of the executable, unless it could not be read, as for processes of other
+  /// users on some platforms.
+  pub exe: Option<PathBuf>,
+  /// Arguments the process was started with, starting with the program. Empty if they
+  /// could not be read.
+  pub cmdline: Vec<String>,
+  /// Working directory of the process when it was detected.
+  pub cwd: Option<PathBuf>,
+  pub parent_pid: Option<u32>,
+  pub start_time: Option<SystemTime>,
+  /// Name of the user the process runs as.
+  pub user: Option<String>,
+  /// How long the event may have happened before it was reported.
+  ///
+  /// For a start, the time since the process started, or since the process list was
+  /// last read if that is shorter, as start times only have second precision. For an
+  /// exit, the time since the process was last seen running.
+  pub detection_latency: Option<Duration>,
+  /// Exit code of an exited process, when it exited normally and the exit was reported
+  /// by [`ProcessBackend::Events`].
+  pub exit_code: Option<i32>,
+}
+
+impl ProcessInfo {
+  fn new(pid: Pid, process: &Process, now: SystemTime) -> Self {
+    let start_time = Some(process.start_time())
+      .filter(|secs| *secs > 0)
+      .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+    Self {
+      pid: pid.as_u32(),
+      name: process.name().to_string_lossy().to_string(),
+      exe: process.exe().map(PathBuf::from),
+      cmdline: process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect(),
+      cwd: process.cwd().map(PathBuf::from),
+      parent_pid: process.parent().map(Pid::as_u32),
+      start_time,
+      user: process.user_id().and_then(user_name),
+      detection_latency: start_time.and_then(|start| now.duration_since(start).ok()),
+      exit_code: None,
+    }
+  }
 }
 
 /// Events that can occur related to processes.
@@ -28,13 +80,61 @@ pub enum ProcessEvent {
 
 callback!(ProcessCallback<T>);
 
+/// How a [`ProcessTrigger`] learns about processes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessBackend {
+  /// Compares the process list every poll interval. Processes that start and exit
+  /// between two polls are never seen.
+  #[default]
+  Poll,
+  /// Notifications from the kernel as processes run programs and exit, with their exit
+  /// codes. Falls back to polling when not available.
+  ///
+  /// On Linux this uses the process connector, which needs `CAP_NET_ADMIN` and is not
+  /// available in containers. Processes are reported when they execute a program, so a
+  /// process replacing its program is reported again and forks that never execute one
+  /// are not reported. Other platforms always poll.
+  Events,
+}
+
+/// A process seen running on the last poll.
+#[derive(Debug)]
+struct KnownProcess {
+  /// Tells the process apart from a later one reusing its pid.
+  start_time: u64,
+  /// Set if the process passed the filters, to be reported when it exits.
+  info: Option<ProcessInfo>,
+  last_seen: SystemTime,
+}
+
+/// A trigger that reports processes starting and exiting, polling the process list.
+///
+/// Filters are applied before the callback runs, so only the processes of interest
+/// wake it up.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let trigger = ProcessTrigger::new(|ctx| async move {
+///   if let ProcessEvent::Started(info) = ctx.data {
+///     println!("cargo started in {:?}", info.cwd);
+///   }
+///   Ok(())
+/// })
+/// .with_name("cargo")
+/// .with_user("alice");
+/// ```
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct ProcessTrigger {
   #[derivative(Debug = "ignore")]
   callback: ProcessCallback<TriggerContext<ProcessEvent>>,
-  known_processes: HashMap<u32, String>,
+  #[derivative(Debug = "ignore")]
+  known_processes: HashMap<u32, KnownProcess>,
+  last_poll: Option<SystemTime>,
   poll_interval: Duration,
+  backend: ProcessBackend,
+  filter: ProcessFilterOptions,
 }
 
 impl ProcessTrigger {
@@ -50,55 +150,240 @@ impl ProcessTrigger {
     assoc
       with_interval(f: F, poll_interval: Duration)
         callback(TriggerContext<ProcessEvent>)
-        async => Self { callback: new_process_callback(f), known_processes: HashMap::new(), poll_interval };
-        blocking => Self { callback: new_process_callback_blocking(f), known_processes: HashMap::new(), poll_interval };
+        async => Self::with_callback(new_process_callback(f), poll_interval);
+        blocking => Self::with_callback(new_process_callback_blocking(f), poll_interval);
+  }
+
+  fn with_callback(
+    callback: ProcessCallback<TriggerContext<ProcessEvent>>,
+    poll_interval: Duration,
+  ) -> Self {
+    Self {
+      callback,
+      known_processes: HashMap::new(),
+      last_poll: None,
+      poll_interval,
+      backend: ProcessBackend::default(),
+      filter: ProcessFilterOptions::default(),
+    }
+  }
+
+  /// Sets how processes are detected. Defaults to [`ProcessBackend::Poll`].
+  pub fn with_backend(mut self, backend: ProcessBackend) -> Self {
+    self.backend = backend;
+    self
   }
 
-  fn refresh_and_get_processes() -> HashMap<u32, String> {
+  /// Only reports processes whose name matches the glob, such as `cargo` or `python*`,
+  /// ignoring case.
+  ///
+  /// Can be given several times, along with [`with_name_regex`](Self::with_name_regex),
+  /// to accept any of the names.
+  pub fn with_name(mut self, pattern: &str) -> Self {
+    self.filter.name_globs.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports processes whose name matches the regular expression.
+  pub fn with_name_regex(mut self, pattern: &str) -> Self {
+    self.filter.name_regexes.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports processes whose executable path matches the glob, such as
+  /// `/usr/bin/*` or `**/cargo`. Processes whose executable cannot be read never match.
+  pub fn with_exe(mut self, pattern: &str) -> Self {
+    self.filter.exe_globs.push(pattern.to_string());
+    self
+  }
+
+  /// Only reports processes running as the user.
+  pub fn with_user(mut self, user: &str) -> Self {
+    self.filter.users.push(user.to_string());
+    self
+  }
+
+  /// Only reports direct children of the process.
+  pub fn with_parent_pid(mut self, pid: u32) -> Self {
+    self.filter.parent_pids.push(pid);
+    self
+  }
+
+  /// Checks a glob pattern for [`with_name`](Self::with_name) or
+  /// [`with_exe`](Self::with_exe) without creating a trigger.
+  pub fn validate_glob(pattern: &str) -> Result<()> {
+    let options = ProcessFilterOptions {
+      name_globs: vec![pattern.to_string()],
+      ..Default::default()
+    };
+    ProcessFilter::new(&options).map(|_| ())
+  }
+
+  /// Checks a pattern for [`with_name_regex`](Self::with_name_regex) without creating a
+  /// trigger.
+  pub fn validate_regex(pattern: &str) -> Result<()> {
+    let options = ProcessFilterOptions {
+      name_regexes: vec![pattern.to_string()],
+      ..Default::default()
+    };
+    ProcessFilter::new(&options).map(|_| ())
+  }
+
+  /// Refreshes the process list, returning the processes that started or exited since
+  /// the last call and pass the filter.
+  ///
+  /// A pid reused by a new process counts as the old process exiting.
+  fn poll(&mut self, filter: &ProcessFilter) -> Vec<ProcessEvent> {
     let mut sys = SYSTEM.lock();
-    sys.refresh_processes(ProcessesToUpdate::All, true);
-
-    sys
-      .processes()
-      .iter()
-      .map(|(pid, proc)| (pid.as_u32(), proc.name().to_string_lossy().to_string()))
-      .collect()
-  }
-
-  /// Handle process changes between current and previous state.
-  async fn handle_process_changes(
-    &self,
-    current_processes: &HashMap<u32, String>,
-    tx: &Sender<TriggerEvent>,
-  ) {
-    // Check for new processes
-    for (pid, name) in current_processes {
-      if !self.known_processes.contains_key(pid) {
-        let context = TriggerContext::new(
-          ProcessEvent::Started(ProcessInfo {
-            pid: *pid,
-            name: name.clone(),
-          }),
-          tx.clone(),
-        );
+    sys.refresh_processes_specifics(
+      ProcessesToUpdate::All,
+      true,
+      info_refresh_kind(UpdateKind::OnlyIfNotSet),
+    );
+    let now = SystemTime::now();
+    let since_last_poll = self
+      .last_poll
+      .replace(now)
+      .and_then(|last_poll| now.duration_since(last_poll).ok());
+    let mut events = Vec::new();
+
+    self.known_processes.retain(|pid, known| {
+      let running = sys
+        .process(Pid::from_u32(*pid))
+        .is_some_and(|process| process.start_time() == known.start_time);
+
+      if running {
+        known.last_seen = now;
+      } else if let Some(mut info) = known.info.take() {
+        info.detection_latency = now.duration_since(known.last_seen).ok();
+        events.push(ProcessEvent::Exited(info));
+      }
+      running
+    });
 
-        send_err!((self.callback)(context).await, "ProcessTrigger", tx, return);
+    for (pid, process) in sys.processes() {
+      if let Entry::Vacant(entry) = self.known_processes.entry(pid.as_u32()) {
+        let mut info = ProcessInfo::new(*pid, process, now);
+        if let Some(since_last_poll) = since_last_poll {
+          info.detection_latency = info
+            .detection_latency
+            .map(|latency| latency.min(since_last_poll))
+            .or(Some(since_last_poll));
+        }
+        let info = filter.accepts(&info).then_some(info);
+        events.extend(info.clone().map(ProcessEvent::Started));
+        entry.insert(KnownProcess {
+          start_time: process.start_time(),
+          info,
+          last_seen: now,
+        });
       }
     }
 
-    // Check for exited processes
-    for (pid, name) in &self.known_processes {
-      if !current_processes.contains_key(pid) {
-        let context = TriggerContext::new(
-          ProcessEvent::Exited(ProcessInfo {
-            pid: *pid,
-            name: name.clone(),
-          }),
-          tx.clone(),
+    events
+  }
+
+  /// Runs the callback for each event, returning whether the trigger should keep going.
+  async fn deliver(&self, events: Vec<ProcessEvent>, rt: &TriggerRuntime) -> bool {
+    for event in events {
+      let context = TriggerContext::new(event, rt.tx.clone());
+      send_err!(
+        (self.callback)(context).await,
+        "ProcessTrigger",
+        &rt.tx,
+        return false
+      );
+    }
+    true
+  }
+
+  #[cfg(target_os = "linux")]
+  async fn listen(
+    &mut self,
+    connector: &crate::linux::ProcConnector,
+    filter: &ProcessFilter,
+    rt: &TriggerRuntime,
+  ) -> Result<()> {
+    loop {
+      let events = tokio::select! {
+        _ = rt.shutdown.cancelled() => break,
+        events = connector.next_events() => events?,
+      };
+
+      let events = events
+        .into_iter()
+        .flat_map(|event| self.handle_proc_event(event, filter))
+        .collect();
+      if !self.deliver(events, rt).await {
+        break;
+      }
+    }
+
+    Ok(())
+  }
+
+  #[cfg(target_os = "linux")]
+  fn handle_proc_event(
+    &mut self,
+    event: crate::linux::ProcEvent,
+    filter: &ProcessFilter,
+  ) -> Vec<ProcessEvent> {
+    use crate::linux::ProcEvent;
+
+    match event {
+      ProcEvent::Exec { pid, latency } => {
+        let pid = Pid::from_u32(pid);
+        let mut sys = SYSTEM.lock();
+        // The program changed, so everything is read again.
+        sys.refresh_processes_specifics(
+          ProcessesToUpdate::Some(&[pid]),
+          false,
+          info_refresh_kind(UpdateKind::Always),
+        );
+        // Already gone and reaped, so there is nothing left to report.
+        let Some(process) = sys.process(pid) else {
+          return Vec::new();
+        };
+
+        let now = SystemTime::now();
+        let mut info = ProcessInfo::new(pid, process, now);
+        info.detection_latency = Some(latency);
+        let info = filter.accepts(&info).then_some(info);
+        self.known_processes.insert(
+          pid.as_u32(),
+          KnownProcess {
+            start_time: process.start_time(),
+            info: info.clone(),
+            last_seen: now,
+          },
         );
+        info.map(ProcessEvent::Started).into_iter().collect()
+      }
+      ProcEvent::Exit {
+        pid,
+        status,
+        latency,
+      } => {
+        SYSTEM.lock().refresh_processes_specifics(
+          ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+          true,
+          ProcessRefreshKind::nothing(),
+        );
+        let Some(mut info) = self
+          .known_processes
+          .remove(&pid)
+          .and_then(|known| known.info)
+        else {
+          return Vec::new();
+        };
 
-        send_err!((self.callback)(context).await, "ProcessTrigger", tx, return);
+        info.detection_latency = Some(latency);
+        // A wait status: the exit code is in the second byte unless a signal killed it.
+        info.exit_code = (status & 0x7f == 0).then_some(((status >> 8) & 0xff) as i32);
+        vec![ProcessEvent::Exited(info)]
       }
+      // Catch up on what was missed.
+      ProcEvent::Lost => self.poll(filter),
     }
   }
 }
@@ -106,22 +391,41 @@ impl ProcessTrigger {
 #[async_trait]
 impl Trigger for ProcessTrigger {
   async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
-    self.known_processes = Self::refresh_and_get_processes();
-
-    loop {
-      if rt.shutdown.is_cancelled() {
-        break;
+    let filter = match ProcessFilter::new(&self.filter) {
+      Ok(filter) => filter,
+      Err(e) => {
+        let _ = rt.tx.send(TriggerEvent::ErrorFatal(e)).await;
+        return Ok(());
       }
-      let current_processes = Self::refresh_and_get_processes();
-      self
-        .handle_process_changes(&current_processes, &rt.tx)
-        .await;
-      self.known_processes = current_processes;
+    };
+
+    // Subscribed before listing the running processes, so none starts unnoticed.
+    #[cfg(target_os = "linux")]
+    let connector = match self.backend {
+      ProcessBackend::Events => crate::linux::ProcConnector::open().await.ok(),
+      ProcessBackend::Poll => None,
+    };
+
+    // Processes already running are not reported as started.
+    self.known_processes.clear();
+    self.last_poll = None;
+    self.poll(&filter);
+
+    #[cfg(target_os = "linux")]
+    if let Some(connector) = connector {
+      return self.listen(&connector, &filter, &rt).await;
+    }
 
+    loop {
       tokio::select! {
         _ = rt.shutdown.cancelled() => break,
         _ = sleep(self.poll_interval) => {}
       }
+
+      let events = self.poll(&filter);
+      if !self.deliver(events, &rt).await {
+        break;
+      }
     }
 
     Ok(())
@@ -132,6 +436,26 @@ impl Trigger for ProcessTrigger {
   }
 }
 
+/// What has to be read to build a [`ProcessInfo`].
+fn info_refresh_kind(update: UpdateKind) -> ProcessRefreshKind {
+  ProcessRefreshKind::nothing()
+    .with_exe(update)
+    .with_cmd(update)
+    .with_cwd(update)
+    .with_user(update)
+}
+
+fn user_name(uid: &Uid) -> Option<String> {
+  let mut users = USERS.lock();
+  // Users created after the list was loaded.
+  if users.get_user_by_id(uid).is_none() {
+    users.refresh();
+  }
+  users
+    .get_user_by_id(uid)
+    .map(|user| user.name().to_string())
+}
+
 pub fn get_process_name(pid: u32) -> Option<String> {
   SYSTEM
     .lock()
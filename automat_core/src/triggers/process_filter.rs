@@ -0,0 +1,91 @@
+use super::process::ProcessInfo;
+use crate::{Error, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::RegexSet;
+
+/// Filters configured on a process trigger, compiled when it starts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProcessFilterOptions {
+  pub(crate) name_globs: Vec<String>,
+  pub(crate) name_regexes: Vec<String>,
+  pub(crate) exe_globs: Vec<String>,
+  pub(crate) users: Vec<String>,
+  pub(crate) parent_pids: Vec<u32>,
+}
+
+/// Decides which processes a trigger reports.
+///
+/// Each kind of filter passes if any of its patterns matches, and a process is
+/// reported if it passes every kind that is set.
+pub(crate) struct ProcessFilter {
+  names: Option<GlobSet>,
+  name_regexes: Option<RegexSet>,
+  exes: Option<GlobSet>,
+  users: Vec<String>,
+  parent_pids: Vec<u32>,
+}
+
+impl ProcessFilter {
+  pub(crate) fn new(options: &ProcessFilterOptions) -> Result<Self> {
+    let name_regexes = if options.name_regexes.is_empty() {
+      None
+    } else {
+      Some(RegexSet::new(&options.name_regexes).map_err(|e| Error::InvalidRegex(e.to_string()))?)
+    };
+
+    Ok(Self {
+      names: glob_set(&options.name_globs, true)?,
+      name_regexes,
+      exes: glob_set(&options.exe_globs, false)?,
+      users: options.users.clone(),
+      parent_pids: options.parent_pids.clone(),
+    })
+  }
+
+  pub(crate) fn accepts(&self, info: &ProcessInfo) -> bool {
+    // Globs and regexes are alternatives for the same name.
+    let by_name = match (&self.names, &self.name_regexes) {
+      (None, None) => true,
+      (names, regexes) => {
+        names.as_ref().is_some_and(|set| set.is_match(&info.name))
+          || regexes.as_ref().is_some_and(|set| set.is_match(&info.name))
+      }
+    };
+
+    by_name
+      && self
+        .exes
+        .as_ref()
+        .is_none_or(|set| info.exe.as_ref().is_some_and(|exe| set.is_match(exe)))
+      && (self.users.is_empty()
+        || info
+          .user
+          .as_ref()
+          .is_some_and(|user| self.users.contains(user)))
+      && (self.parent_pids.is_empty()
+        || info
+          .parent_pid
+          .is_some_and(|pid| self.parent_pids.contains(&pid)))
+  }
+}
+
+/// Compiles patterns into a set, or `None` if there are none.
+pub(crate) fn glob_set(patterns: &[String], case_insensitive: bool) -> Result<Option<GlobSet>> {
+  if patterns.is_empty() {
+    return Ok(None);
+  }
+
+  let mut builder = GlobSetBuilder::new();
+  for pattern in patterns {
+    let glob = GlobBuilder::new(pattern)
+      .case_insensitive(case_insensitive)
+      .build()
+      .map_err(|e| Error::InvalidGlob(format!("`{}`: {}", pattern, e.kind())))?;
+    builder.add(glob);
+  }
+
+  builder
+    .build()
+    .map(Some)
+    .map_err(|e| Error::InvalidGlob(e.to_string()))
+}
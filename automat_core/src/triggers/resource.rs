@@ -0,0 +1,456 @@
+use super::process_filter::glob_set;
+use crate::triggers::context::{TriggerContext, TriggerEvent};
+use crate::{Error, Result, Trigger, TriggerRuntime, callback, pair_api, send_err};
+use async_trait::async_trait;
+use derivative::Derivative;
+use globset::GlobSet;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{Disks, ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::time::{Instant, sleep};
+
+callback!(ResourceCallback<T>);
+
+/// A quantity watched by a [`ResourceTrigger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceMetric {
+  /// Usage of all CPUs together, in percent.
+  SystemCpu,
+  /// CPU usage of each process whose name matches the glob, ignoring case, in percent
+  /// of one core. Processes using several cores go above 100.
+  ProcessCpu(String),
+  /// Resident memory of each process whose name matches the glob, ignoring case, in
+  /// bytes.
+  ProcessMemory(String),
+  /// Memory available to applications without swapping, in bytes.
+  FreeMemory,
+  /// Space available on the disk holding the path, in bytes. The disk is chosen when
+  /// the trigger starts, and is not measured while it is unmounted.
+  DiskFree(PathBuf),
+}
+
+/// Where a metric's value stops being acceptable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+  Above(f64),
+  Below(f64),
+}
+
+impl Threshold {
+  fn is_exceeded_by(self, value: f64) -> bool {
+    match self {
+      Self::Above(limit) => value > limit,
+      Self::Below(limit) => value < limit,
+    }
+  }
+
+  /// Whether the value is back on the acceptable side by at least `margin`.
+  fn is_recovered_by(self, value: f64, margin: f64) -> bool {
+    match self {
+      Self::Above(limit) => value <= limit - margin,
+      Self::Below(limit) => value >= limit + margin,
+    }
+  }
+}
+
+/// A value observed for a metric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceReading {
+  pub metric: ResourceMetric,
+  pub value: f64,
+  /// The process measured, for process metrics.
+  pub pid: Option<u32>,
+  pub process_name: Option<String>,
+}
+
+/// Events reported by a [`ResourceTrigger`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceEvent {
+  /// The value stayed past the threshold for the sustained duration.
+  Exceeded(ResourceReading),
+  /// The value came back past the threshold by the hysteresis margin.
+  Recovered(ResourceReading),
+  /// The value stopped being measured after `Exceeded`, because the process exited or
+  /// the disk was unmounted. Holds the last reading, and is sent instead of `Recovered`.
+  Gone(ResourceReading),
+}
+
+impl ResourceEvent {
+  pub fn reading(&self) -> &ResourceReading {
+    match self {
+      Self::Exceeded(reading) | Self::Recovered(reading) | Self::Gone(reading) => reading,
+    }
+  }
+}
+
+/// Tracks one measured value, the system's or a process's, against the threshold.
+#[derive(Debug, Default)]
+struct ThresholdState {
+  /// When the value went past the threshold, while it stays there.
+  exceeded_since: Option<Instant>,
+  /// The latest reading, once `Exceeded` was reported and until `Recovered` is.
+  reported: Option<ResourceReading>,
+}
+
+/// A trigger that fires when a system or process resource crosses a threshold.
+///
+/// Values are sampled every second by default. A value has to stay past the threshold
+/// for the [sustained duration](Self::with_duration) before `Exceeded` is reported, and
+/// is only reported as `Recovered`, allowing the next `Exceeded`, once it is back past
+/// the threshold by the [hysteresis margin](Self::with_hysteresis).
+///
+/// Process metrics are tracked per process, so each matching process is reported on
+/// its own. A process that exits while reported as exceeded is reported as `Gone`.
+///
+/// ```no_run
+/// use automat_core::*;
+/// use std::time::Duration;
+///
+/// let trigger = ResourceTrigger::new(
+///   ResourceMetric::ProcessMemory("rust-analyzer".to_string()),
+///   Threshold::Above(4e9),
+///   |ctx| async move {
+///     if let ResourceEvent::Exceeded(reading) = ctx.data {
+///       println!("rust-analyzer ({:?}) uses {} bytes", reading.pid, reading.value);
+///     }
+///     Ok(())
+///   },
+/// )
+/// .with_duration(Duration::from_secs(30))
+/// .with_hysteresis(5e8);
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ResourceTrigger {
+  metric: ResourceMetric,
+  threshold: Threshold,
+  duration: Duration,
+  hysteresis: f64,
+  sample_interval: Duration,
+  #[derivative(Debug = "ignore")]
+  callback: ResourceCallback<TriggerContext<ResourceEvent>>,
+}
+
+impl ResourceTrigger {
+  pair_api! {
+    assoc
+      new(metric: ResourceMetric, threshold: Threshold, f: F)
+        callback(TriggerContext<ResourceEvent>)
+        async => Self::with_callback(metric, threshold, new_resource_callback(f));
+        blocking => Self::with_callback(metric, threshold, new_resource_callback_blocking(f));
+  }
+
+  fn with_callback(
+    metric: ResourceMetric,
+    threshold: Threshold,
+    callback: ResourceCallback<TriggerContext<ResourceEvent>>,
+  ) -> Self {
+    Self {
+      metric,
+      threshold,
+      duration: Duration::ZERO,
+      hysteresis: 0.0,
+      sample_interval: Duration::from_secs(1),
+      callback,
+    }
+  }
+
+  /// Sets how long the value has to stay past the threshold before it is reported.
+  /// Defaults to reporting the first sample past it.
+  pub fn with_duration(mut self, duration: Duration) -> Self {
+    self.duration = duration;
+    self
+  }
+
+  /// Sets how far, in the metric's unit, the value has to come back past the threshold
+  /// to count as recovered. Defaults to 0.
+  pub fn with_hysteresis(mut self, margin: f64) -> Self {
+    self.hysteresis = margin.abs();
+    self
+  }
+
+  /// Sets how often the metric is sampled.
+  pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+    self.sample_interval = interval;
+    self
+  }
+
+  /// Updates the state of one value, returning the event to report, if any.
+  fn update(&self, state: &mut ThresholdState, reading: ResourceReading) -> Option<ResourceEvent> {
+    if let Some(last) = &mut state.reported {
+      if self
+        .threshold
+        .is_recovered_by(reading.value, self.hysteresis)
+      {
+        *state = ThresholdState::default();
+        return Some(ResourceEvent::Recovered(reading));
+      }
+      *last = reading;
+      return None;
+    }
+
+    if !self.threshold.is_exceeded_by(reading.value) {
+      state.exceeded_since = None;
+      return None;
+    }
+
+    let since = *state.exceeded_since.get_or_insert_with(Instant::now);
+    if since.elapsed() < self.duration {
+      return None;
+    }
+    state.reported = Some(reading.clone());
+    Some(ResourceEvent::Exceeded(reading))
+  }
+}
+
+#[async_trait]
+impl Trigger for ResourceTrigger {
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    let mut sampler = match Sampler::new(&self.metric) {
+      Ok(sampler) => sampler,
+      Err(e) => {
+        let _ = rt.tx.send(TriggerEvent::ErrorFatal(e)).await;
+        return Ok(());
+      }
+    };
+    // CPU usage is measured between two samples, so the first one only sets the baseline.
+    sampler.sample(&self.metric);
+    let mut states: HashMap<Option<u32>, ThresholdState> = HashMap::new();
+
+    loop {
+      tokio::select! {
+        _ = rt.shutdown.cancelled() => break,
+        _ = sleep(self.sample_interval) => {}
+      }
+
+      let readings = sampler.sample(&self.metric);
+      // Values no longer measured, like those of processes that exited, are forgotten,
+      // and those reported as exceeded are reported as gone.
+      let mut events: Vec<ResourceEvent> = states
+        .extract_if(|pid, _| !readings.iter().any(|reading| reading.pid == *pid))
+        .filter_map(|(_, state)| state.reported.map(ResourceEvent::Gone))
+        .collect();
+
+      for reading in readings {
+        let state = states.entry(reading.pid).or_default();
+        events.extend(self.update(state, reading));
+      }
+
+      for event in events {
+        let context = TriggerContext::new(event, rt.tx.clone());
+        send_err!(
+          (self.callback)(context).await,
+          "ResourceTrigger",
+          &rt.tx,
+          return Ok(())
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  fn name(&self) -> String {
+    "ResourceTrigger".to_string()
+  }
+}
+
+/// Reads the current values of a metric.
+struct Sampler {
+  system: System,
+  disks: Disks,
+  /// Process names the metric applies to.
+  names: Option<GlobSet>,
+  /// Mount point of the disk measured for `DiskFree`.
+  mount_point: Option<PathBuf>,
+}
+
+impl Sampler {
+  fn new(metric: &ResourceMetric) -> Result<Self> {
+    let names = match metric {
+      ResourceMetric::ProcessCpu(name) | ResourceMetric::ProcessMemory(name) => {
+        glob_set(std::slice::from_ref(name), true)?
+      }
+      _ => None,
+    };
+
+    let (disks, mount_point) = match metric {
+      ResourceMetric::DiskFree(path) => {
+        let disks = Disks::new_with_refreshed_list();
+        let mount_point = disk_for(&disks, path)
+          .ok_or_else(|| Error::DiskNotFound(path.clone()))?
+          .mount_point()
+          .to_path_buf();
+        (disks, Some(mount_point))
+      }
+      _ => (Disks::new(), None),
+    };
+
+    Ok(Self {
+      system: System::new(),
+      disks,
+      names,
+      mount_point,
+    })
+  }
+
+  fn sample(&mut self, metric: &ResourceMetric) -> Vec<ResourceReading> {
+    let reading = |value: f64| ResourceReading {
+      metric: metric.clone(),
+      value,
+      pid: None,
+      process_name: None,
+    };
+
+    match metric {
+      ResourceMetric::SystemCpu => {
+        self.system.refresh_cpu_usage();
+        vec![reading(f64::from(self.system.global_cpu_usage()))]
+      }
+      ResourceMetric::FreeMemory => {
+        self.system.refresh_memory();
+        vec![reading(self.system.available_memory() as f64)]
+      }
+      ResourceMetric::DiskFree(_) => {
+        self.disks.refresh(true);
+        // Only the disk chosen at the start, so an unmounted disk is reported as gone
+        // instead of switching to the one mounted above it.
+        self
+          .disks
+          .list()
+          .iter()
+          .find(|disk| Some(disk.mount_point()) == self.mount_point.as_deref())
+          .map(|disk| reading(disk.available_space() as f64))
+          .into_iter()
+          .collect()
+      }
+      ResourceMetric::ProcessCpu(_) | ResourceMetric::ProcessMemory(_) => {
+        self.system.refresh_processes_specifics(
+          ProcessesToUpdate::All,
+          true,
+          ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let names = self.names.as_ref();
+        self
+          .system
+          .processes()
+          .iter()
+          .filter_map(|(pid, process)| {
+            let name = process.name().to_string_lossy();
+            if !names.is_some_and(|names| names.is_match(name.as_ref())) {
+              return None;
+            }
+
+            let value = match metric {
+              ResourceMetric::ProcessCpu(_) => f64::from(process.cpu_usage()),
+              _ => process.memory() as f64,
+            };
+            Some(ResourceReading {
+              pid: Some(pid.as_u32()),
+              process_name: Some(name.to_string()),
+              ..reading(value)
+            })
+          })
+          .collect()
+      }
+    }
+  }
+}
+
+/// The disk holding `path`: the one with the longest mount point containing it.
+fn disk_for<'a>(disks: &'a Disks, path: &Path) -> Option<&'a sysinfo::Disk> {
+  let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+  disks
+    .list()
+    .iter()
+    .filter(|disk| path.starts_with(disk.mount_point()))
+    .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn trigger(threshold: Threshold) -> ResourceTrigger {
+    ResourceTrigger::new_blocking(ResourceMetric::SystemCpu, threshold, |_| Ok(()))
+  }
+
+  fn reading(value: f64) -> ResourceReading {
+    ResourceReading {
+      metric: ResourceMetric::SystemCpu,
+      value,
+      pid: None,
+      process_name: None,
+    }
+  }
+
+  /// Feeds the values to the trigger and returns the events it reports.
+  fn events(trigger: &ResourceTrigger, state: &mut ThresholdState, values: &[f64]) -> Vec<String> {
+    values
+      .iter()
+      .filter_map(|value| trigger.update(state, reading(*value)))
+      .map(|event| match event {
+        ResourceEvent::Exceeded(reading) => format!("exceeded {}", reading.value),
+        ResourceEvent::Recovered(reading) => format!("recovered {}", reading.value),
+        ResourceEvent::Gone(reading) => format!("gone {}", reading.value),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn reports_once_until_recovered_past_the_margin() {
+    let trigger = trigger(Threshold::Above(80.0)).with_hysteresis(10.0);
+    let mut state = ThresholdState::default();
+
+    assert_eq!(
+      events(
+        &trigger,
+        &mut state,
+        &[50.0, 85.0, 95.0, 79.0, 71.0, 70.0, 81.0]
+      ),
+      ["exceeded 85", "recovered 70", "exceeded 81"]
+    );
+  }
+
+  #[test]
+  fn recovers_upwards_below_a_floor() {
+    let trigger = trigger(Threshold::Below(1e9)).with_hysteresis(2e8);
+    let mut state = ThresholdState::default();
+
+    assert_eq!(
+      events(&trigger, &mut state, &[2e9, 5e8, 1.1e9, 1.2e9, 9e8]),
+      [
+        "exceeded 500000000",
+        "recovered 1200000000",
+        "exceeded 900000000"
+      ]
+    );
+  }
+
+  #[test]
+  fn keeps_the_latest_reading_while_reported() {
+    let trigger = trigger(Threshold::Above(80.0));
+    let mut state = ThresholdState::default();
+
+    events(&trigger, &mut state, &[90.0, 99.0]);
+    assert_eq!(state.reported, Some(reading(99.0)));
+    events(&trigger, &mut state, &[80.0]);
+    assert_eq!(state.reported, None);
+  }
+
+  #[test]
+  fn waits_for_the_sustained_duration() {
+    let duration = Duration::from_millis(50);
+    let trigger = trigger(Threshold::Above(80.0)).with_duration(duration);
+    let mut state = ThresholdState::default();
+
+    assert!(events(&trigger, &mut state, &[90.0]).is_empty());
+    std::thread::sleep(duration);
+    // Dipping under the threshold starts the duration over.
+    assert!(events(&trigger, &mut state, &[70.0, 90.0]).is_empty());
+    std::thread::sleep(duration);
+    assert_eq!(events(&trigger, &mut state, &[90.0]), ["exceeded 90"]);
+  }
+}
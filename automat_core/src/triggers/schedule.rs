@@ -0,0 +1,289 @@
+use crate::{Error, Result, Trigger, TriggerContext, TriggerRuntime, callback, pair_api, send_err};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use derivative::Derivative;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::sleep;
+
+callback!(ScheduleCallback<T>);
+
+/// Longest single sleep while waiting for the next fire time.
+///
+/// Tokio timers use a monotonic clock that may not advance while the system is suspended,
+/// so the wall clock is re-checked at least this often.
+const MAX_SLEEP: Duration = Duration::from_secs(1);
+
+/// How late a fire may be before it counts as missed.
+const LATE_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// What to do with fire times that passed while the trigger could not run,
+/// e.g. because the system was suspended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedFires {
+  /// Drop missed fire times and wait for the next one.
+  Skip,
+  /// Fire once for the most recent missed fire time.
+  #[default]
+  FireOnce,
+  /// Fire for every missed fire time, in order.
+  FireAll,
+}
+
+/// Payload passed to schedule callbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleEvent {
+  /// When the fire was scheduled, in the trigger's timezone.
+  pub scheduled: DateTime<FixedOffset>,
+  /// When the trigger actually fired, in the trigger's timezone.
+  pub actual: DateTime<FixedOffset>,
+}
+
+impl ScheduleEvent {
+  /// Returns how late the fire was compared to its schedule.
+  pub fn lateness(&self) -> Duration {
+    (self.actual - self.scheduled).to_std().unwrap_or_default()
+  }
+}
+
+/// ScheduleTrigger fires according to a cron expression.
+///
+/// Expressions use the `sec min hour day-of-month month day-of-week [year]` format;
+/// classic five-field expressions without seconds are also accepted and fire at second 0.
+/// Fire times are computed in wall-clock time of the configured timezone (local time by
+/// default):
+///
+/// - fire times inside a skipped DST hour fire once, as soon as the clock leaves the gap;
+/// - fire times inside a repeated DST hour fire once, on the first pass.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// // Every weekday at 09:30:00 in Warsaw.
+/// let trigger = ScheduleTrigger::new_blocking("0 30 9 * * Mon-Fri", |ctx| {
+///   println!("scheduled at {}, fired at {}", ctx.data.scheduled, ctx.data.actual);
+///   Ok(())
+/// })
+/// .with_timezone(Tz::Europe__Warsaw)
+/// .with_missed_fires(MissedFires::Skip);
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ScheduleTrigger {
+  expression: String,
+  schedule: std::result::Result<Schedule, String>,
+  timezone: Option<Tz>,
+  missed_fires: MissedFires,
+  #[derivative(Debug = "ignore")]
+  callback: ScheduleCallback<TriggerContext<ScheduleEvent>>,
+}
+
+impl ScheduleTrigger {
+  pair_api! {
+    assoc
+      new(expression: &str, f: F)
+        callback(TriggerContext<ScheduleEvent>)
+        async => Self::with_callback(expression, new_schedule_callback(f));
+        blocking => Self::with_callback(expression, new_schedule_callback_blocking(f));
+  }
+
+  fn with_callback(
+    expression: &str,
+    callback: ScheduleCallback<TriggerContext<ScheduleEvent>>,
+  ) -> Self {
+    Self {
+      expression: expression.to_string(),
+      schedule: parse_schedule(expression),
+      timezone: None,
+      missed_fires: MissedFires::default(),
+      callback,
+    }
+  }
+
+  /// Evaluates the schedule in the given timezone instead of local time.
+  pub fn with_timezone(mut self, timezone: Tz) -> Self {
+    self.timezone = Some(timezone);
+    self
+  }
+
+  /// Sets how fire times missed during suspend are handled.
+  pub fn with_missed_fires(mut self, missed_fires: MissedFires) -> Self {
+    self.missed_fires = missed_fires;
+    self
+  }
+
+  /// Checks a cron expression without creating a trigger.
+  ///
+  /// Accepts the same formats as [`new`](Self::new).
+  pub fn validate_expression(expression: &str) -> Result<()> {
+    parse_schedule(expression)
+      .map(|_| ())
+      .map_err(Error::InvalidSchedule)
+  }
+
+  /// Returns the cron expression this trigger was created with.
+  pub fn expression(&self) -> &str {
+    &self.expression
+  }
+
+  /// Returns the first fire time strictly after `after`, or `None` if the schedule has
+  /// no more fire times.
+  pub fn next_fire_after(&self, after: DateTime<Utc>) -> Option<DateTime<FixedOffset>> {
+    let schedule = self.schedule.as_ref().ok()?;
+    let next = match self.timezone {
+      Some(tz) => next_fire(schedule, &tz, after),
+      None => next_fire(schedule, &Local, after),
+    }?;
+    Some(self.localize(next))
+  }
+
+  fn localize(&self, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match self.timezone {
+      Some(tz) => instant.with_timezone(&tz).fixed_offset(),
+      None => instant.with_timezone(&Local).fixed_offset(),
+    }
+  }
+
+  /// Collects fire times from `first` up to `now` and filters them by the missed fire
+  /// policy. Also returns the last collected fire time, fired or not.
+  fn due_fires(
+    &self,
+    first: DateTime<FixedOffset>,
+    now: DateTime<Utc>,
+  ) -> (Vec<DateTime<FixedOffset>>, DateTime<Utc>) {
+    let mut due = vec![first];
+    let mut last = first.to_utc();
+    while let Some(next) = self.next_fire_after(last) {
+      if next.to_utc() > now {
+        break;
+      }
+      last = next.to_utc();
+      due.push(next);
+    }
+
+    let is_late = |t: &DateTime<FixedOffset>| {
+      (now - t.to_utc())
+        .to_std()
+        .is_ok_and(|late| late > LATE_TOLERANCE)
+    };
+
+    let fires = match self.missed_fires {
+      MissedFires::FireAll => due,
+      MissedFires::Skip => due.into_iter().filter(|t| !is_late(t)).collect(),
+      MissedFires::FireOnce => {
+        if due.iter().any(|t| !is_late(t)) {
+          due.into_iter().filter(|t| !is_late(t)).collect()
+        } else {
+          due.pop().into_iter().collect()
+        }
+      }
+    };
+
+    (fires, last)
+  }
+}
+
+/// Parses a cron expression, accepting five-field expressions without seconds.
+fn parse_schedule(expression: &str) -> std::result::Result<Schedule, String> {
+  let expression = expression.trim();
+  let normalized = if expression.split_whitespace().count() == 5 {
+    format!("0 {}", expression)
+  } else {
+    expression.to_string()
+  };
+
+  Schedule::from_str(&normalized).map_err(|e| {
+    // Expression errors draw a caret under the normalized expression above the reason;
+    // keep only the reason.
+    let message = e.to_string();
+    let reason = message.lines().last().unwrap_or_default().to_string();
+    format!("`{}`: {}", expression, reason)
+  })
+}
+
+/// Finds the first fire time strictly after `after`, evaluating the schedule in the
+/// wall-clock time of `tz`.
+fn next_fire<T: TimeZone>(
+  schedule: &Schedule,
+  tz: &T,
+  after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+  // The schedule is evaluated on naive wall-clock times, carried as UTC values.
+  let wall_clock = after.with_timezone(tz).naive_local().and_utc();
+
+  schedule
+    .after(&wall_clock)
+    .take(1024)
+    .filter_map(|candidate| resolve_wall_clock(tz, candidate.naive_utc()))
+    .find(|instant| *instant > after)
+}
+
+/// Maps a wall-clock time to an instant, handling DST transitions.
+///
+/// Ambiguous times resolve to their first occurrence, and times inside a gap resolve
+/// to the first valid minute after it.
+fn resolve_wall_clock<T: TimeZone>(tz: &T, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+  if let Some(instant) = tz.from_local_datetime(&naive).earliest() {
+    return Some(instant.to_utc());
+  }
+
+  let minute = naive
+    .date()
+    .and_hms_opt(naive.time().hour(), naive.time().minute(), 0)?;
+  (1..=24 * 60).find_map(|m| {
+    tz.from_local_datetime(&(minute + chrono::Duration::minutes(m)))
+      .earliest()
+      .map(|instant| instant.to_utc())
+  })
+}
+
+#[async_trait]
+impl Trigger for ScheduleTrigger {
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    if let Err(err) = &self.schedule {
+      return Err(Error::InvalidSchedule(err.clone()));
+    }
+
+    let mut last = Utc::now();
+
+    while let Some(next) = self.next_fire_after(last) {
+      loop {
+        let remaining = (next.to_utc() - Utc::now()).to_std().unwrap_or_default();
+        if remaining.is_zero() {
+          break;
+        }
+
+        tokio::select! {
+          _ = rt.shutdown.cancelled() => return Ok(()),
+          _ = sleep(remaining.min(MAX_SLEEP)) => {}
+        }
+      }
+
+      let (due, last_due) = self.due_fires(next, Utc::now());
+      last = last_due;
+
+      for scheduled in due {
+        let event = ScheduleEvent {
+          scheduled,
+          actual: self.localize(Utc::now()),
+        };
+        let ctx = TriggerContext::new(event, rt.tx.clone());
+
+        send_err!(
+          (self.callback)(ctx).await,
+          "ScheduleTrigger",
+          &rt.tx,
+          return Ok(())
+        );
+      }
+    }
+
+    Ok(())
+  }
+
+  fn name(&self) -> String {
+    format!("ScheduleTrigger ({})", self.expression)
+  }
+}
@@ -1,11 +1,20 @@
-use crate::triggers::context::send_error;
-use crate::{callback, pair_api, Result, Trigger, TriggerContext, TriggerRuntime, Window};
+use crate::{
+  Result, Trigger, TriggerContext, TriggerRuntime, Window, callback, pair_api, send_err,
+};
 use async_trait::async_trait;
 use derivative::Derivative;
+use tokio::time::{Duration, interval};
 
 callback!(WindowChangeCallback<T>);
 
+/// How often the focused window is polled when focus events are not available.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// WindowTrigger trigger when the current window changes.
+///
+/// On Linux, focus changes are delivered as soon as the window manager updates
+/// `_NET_ACTIVE_WINDOW`. Window managers without EWMH support, and other platforms,
+/// fall back to polling the focused window every 500ms.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct WindowTrigger {
@@ -22,29 +31,37 @@ impl WindowTrigger {
         async => Self { last_window: None, callback: new_window_change_callback(f) };
         blocking => Self { last_window: None, callback: new_window_change_callback_blocking(f) };
   }
-}
 
-#[async_trait]
-impl Trigger for WindowTrigger {
-  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
-    use tokio::time::{interval, Duration};
-    let mut ticker = interval(Duration::from_millis(500));
+  /// Calls the callback if `window` differs from the last focused window.
+  ///
+  /// Returns false if the trigger should stop.
+  async fn focus_changed(&mut self, window: Window, rt: &TriggerRuntime) -> bool {
+    if self.last_window.as_ref() == Some(&window) {
+      return true;
+    }
+
+    self.last_window = Some(window);
+    let ctx = TriggerContext::new(window, rt.tx.clone());
+    send_err!(
+      (self.callback)(ctx).await,
+      "WindowTrigger",
+      &rt.tx,
+      return false
+    );
+    true
+  }
+
+  async fn poll(&mut self, rt: &TriggerRuntime) -> Result<()> {
+    let mut ticker = interval(POLL_INTERVAL);
 
     loop {
       tokio::select! {
         _ = rt.shutdown.cancelled() => break,
         _ = ticker.tick() => {
-          if let Some(window) = Window::current() {
-            if self.last_window.as_ref() != Some(&window) {
-              self.last_window = Some(window.clone());
-              let ctx = TriggerContext::new(window.clone(), rt.tx.clone());
-
-              if let Err(err) = (self.callback)(ctx).await {
-                if !send_error(&rt.tx, err, "WindowTrigger").await {
-                  break;
-                }
-              }
-            }
+          if let Some(window) = Window::current()
+            && !self.focus_changed(window, rt).await
+          {
+            break;
           }
         }
       }
@@ -52,8 +69,88 @@ impl Trigger for WindowTrigger {
 
     Ok(())
   }
+}
+
+#[async_trait]
+impl Trigger for WindowTrigger {
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    if let Some(watcher) = linux::ActiveWindowWatcher::open() {
+      return watcher.run(self, &rt).await;
+    }
+
+    self.poll(&rt).await
+  }
 
   fn name(&self) -> String {
     "WindowTrigger".to_string()
   }
 }
+
+#[cfg(target_os = "linux")]
+mod linux {
+  use super::WindowTrigger;
+  use crate::linux::{NET_ACTIVE_WINDOW, XEventConnection, existing_atom, window_property};
+  use crate::{Result, TriggerRuntime, Window, WindowIdentifier};
+  use x11::xlib::{Atom, PropertyChangeMask, PropertyNotify, XSelectInput};
+
+  /// Follows `_NET_ACTIVE_WINDOW` on the root window through PropertyNotify events.
+  pub(super) struct ActiveWindowWatcher {
+    conn: XEventConnection,
+    active_window: Atom,
+  }
+
+  impl ActiveWindowWatcher {
+    /// Subscribes to root window property changes.
+    ///
+    /// Returns `None` if the display can't be opened or the window manager does not
+    /// maintain `_NET_ACTIVE_WINDOW`.
+    pub(super) fn open() -> Option<Self> {
+      let conn = XEventConnection::open().ok()?;
+      let active_window = existing_atom(conn.display(), NET_ACTIVE_WINDOW)?;
+      window_property(conn.display(), conn.root(), active_window)?;
+
+      unsafe { XSelectInput(conn.display(), conn.root(), PropertyChangeMask) };
+
+      Some(Self {
+        conn,
+        active_window,
+      })
+    }
+
+    fn current(&self) -> Option<Window> {
+      window_property(self.conn.display(), self.conn.root(), self.active_window)
+        .filter(|id| *id != 0)
+        .map(|id| Window::new(WindowIdentifier::new(id)))
+    }
+
+    pub(super) async fn run(self, trigger: &mut WindowTrigger, rt: &TriggerRuntime) -> Result<()> {
+      if let Some(window) = self.current()
+        && !trigger.focus_changed(window, rt).await
+      {
+        return Ok(());
+      }
+
+      loop {
+        let changed = tokio::select! {
+          _ = rt.shutdown.cancelled() => break,
+          event = self.conn.next_event() => {
+            let event = event?;
+            event.get_type() == PropertyNotify
+              && unsafe { event.property.atom } == self.active_window
+          }
+        };
+
+        // Focus moving to the desktop or a panel clears the property; keep the last window.
+        if changed
+          && let Some(window) = self.current()
+          && !trigger.focus_changed(window, rt).await
+        {
+          break;
+        }
+      }
+
+      Ok(())
+    }
+  }
+}
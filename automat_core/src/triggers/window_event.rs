@@ -0,0 +1,255 @@
+use crate::{
+  Result, Trigger, TriggerContext, TriggerRuntime, Window, WindowState, callback,
+  get_window_position, get_window_size, get_window_state, get_window_title, list_windows, pair_api,
+  send_err, send_error,
+};
+use async_trait::async_trait;
+use derivative::Derivative;
+use std::collections::HashMap;
+use tokio::time::{Duration, interval};
+
+callback!(WindowEventCallback<T>);
+
+/// Payload passed to window event callbacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowEvent {
+  /// A new window appeared.
+  Opened(Window),
+  /// A window was closed. It can no longer be queried, so its last known title is included.
+  Closed {
+    window: Window,
+    title: Option<String>,
+  },
+  /// A window received focus.
+  Focused(Window),
+  /// A window lost focus.
+  Unfocused(Window),
+  /// The window title changed.
+  TitleChanged {
+    window: Window,
+    old: Option<String>,
+    new: Option<String>,
+  },
+  /// The window's top-left corner moved, in screen coordinates.
+  Moved {
+    window: Window,
+    old: (i32, i32),
+    new: (i32, i32),
+  },
+  /// The window was resized, in pixels.
+  Resized {
+    window: Window,
+    old: (u32, u32),
+    new: (u32, u32),
+  },
+  /// The window was minimized, maximized, hidden, restored or disabled.
+  StateChanged {
+    window: Window,
+    old: WindowState,
+    new: WindowState,
+  },
+}
+
+impl WindowEvent {
+  /// Returns the window this event is about.
+  pub fn window(&self) -> Window {
+    match self {
+      WindowEvent::Opened(window)
+      | WindowEvent::Focused(window)
+      | WindowEvent::Unfocused(window)
+      | WindowEvent::Closed { window, .. }
+      | WindowEvent::TitleChanged { window, .. }
+      | WindowEvent::Moved { window, .. }
+      | WindowEvent::Resized { window, .. }
+      | WindowEvent::StateChanged { window, .. } => *window,
+    }
+  }
+}
+
+/// Last observed properties of a window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WindowSnapshot {
+  window: Window,
+  title: Option<String>,
+  position: Option<(i32, i32)>,
+  size: Option<(u32, u32)>,
+  state: Option<WindowState>,
+}
+
+impl WindowSnapshot {
+  fn capture(window: Window) -> Self {
+    let id = window.id();
+    Self {
+      window,
+      title: get_window_title(id),
+      position: get_window_position(id),
+      size: get_window_size(id),
+      state: get_window_state(id).ok(),
+    }
+  }
+
+  /// Returns the events that turn `self` into `new`.
+  fn diff(&self, new: &WindowSnapshot) -> Vec<WindowEvent> {
+    let window = self.window;
+    let mut events = Vec::new();
+
+    if self.title != new.title {
+      events.push(WindowEvent::TitleChanged {
+        window,
+        old: self.title.clone(),
+        new: new.title.clone(),
+      });
+    }
+    if let (Some(old), Some(new)) = (self.position, new.position)
+      && old != new
+    {
+      events.push(WindowEvent::Moved { window, old, new });
+    }
+    if let (Some(old), Some(new)) = (self.size, new.size)
+      && old != new
+    {
+      events.push(WindowEvent::Resized { window, old, new });
+    }
+    if let (Some(old), Some(new)) = (self.state, new.state)
+      && old != new
+    {
+      events.push(WindowEvent::StateChanged { window, old, new });
+    }
+
+    events
+  }
+}
+
+/// WindowEventTrigger reports windows being opened, closed, focused, retitled, moved,
+/// resized and changing state.
+///
+/// Windows are polled every 500ms by default and compared with the previous poll, so
+/// changes that are undone within one interval are not reported. Windows that are already
+/// open when the trigger starts do not produce `Opened` events.
+///
+/// ```no_run
+/// use automat_core::*;
+///
+/// let trigger = WindowEventTrigger::new_blocking(|ctx| {
+///   if let WindowEvent::Opened(window) = ctx.data {
+///     println!("opened {:?}", window.title());
+///   }
+///   Ok(())
+/// });
+/// ```
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WindowEventTrigger {
+  poll_interval: Duration,
+  #[derivative(Debug = "ignore")]
+  callback: WindowEventCallback<TriggerContext<WindowEvent>>,
+}
+
+impl WindowEventTrigger {
+  pair_api! {
+    assoc
+      new(f: F)
+        callback(TriggerContext<WindowEvent>)
+        async => Self::with_interval(f, Duration::from_millis(500));
+        blocking => Self::with_interval_blocking(f, Duration::from_millis(500));
+  }
+
+  pair_api! {
+    assoc
+      with_interval(f: F, poll_interval: Duration)
+        callback(TriggerContext<WindowEvent>)
+        async => Self { callback: new_window_event_callback(f), poll_interval };
+        blocking => Self { callback: new_window_event_callback_blocking(f), poll_interval };
+  }
+
+  /// Lists windows and captures their properties.
+  fn snapshot() -> Result<HashMap<u64, WindowSnapshot>> {
+    Ok(
+      list_windows()?
+        .into_iter()
+        .map(|window| (window.id().as_u64(), WindowSnapshot::capture(window)))
+        .collect(),
+    )
+  }
+
+  /// Compares two polls and returns the events in the order they are delivered:
+  /// closed, opened, property changes, then focus changes.
+  fn diff(
+    old: &HashMap<u64, WindowSnapshot>,
+    new: &HashMap<u64, WindowSnapshot>,
+    old_focus: Option<Window>,
+    new_focus: Option<Window>,
+  ) -> Vec<WindowEvent> {
+    let mut events = Vec::new();
+
+    events.extend(
+      old
+        .iter()
+        .filter(|(id, _)| !new.contains_key(id))
+        .map(|(_, snapshot)| WindowEvent::Closed {
+          window: snapshot.window,
+          title: snapshot.title.clone(),
+        }),
+    );
+    events.extend(
+      new
+        .iter()
+        .filter(|(id, _)| !old.contains_key(id))
+        .map(|(_, snapshot)| WindowEvent::Opened(snapshot.window)),
+    );
+    for (id, snapshot) in new {
+      if let Some(previous) = old.get(id) {
+        events.extend(previous.diff(snapshot));
+      }
+    }
+
+    if old_focus != new_focus {
+      events.extend(old_focus.map(WindowEvent::Unfocused));
+      events.extend(new_focus.map(WindowEvent::Focused));
+    }
+
+    events
+  }
+}
+
+#[async_trait]
+impl Trigger for WindowEventTrigger {
+  async fn start(&mut self, rt: TriggerRuntime) -> Result<()> {
+    let mut ticker = interval(self.poll_interval);
+    let mut windows = Self::snapshot()?;
+    let mut focus = Window::current();
+
+    loop {
+      tokio::select! {
+        _ = rt.shutdown.cancelled() => break,
+        _ = ticker.tick() => {
+          let current = match Self::snapshot() {
+            Ok(current) => current,
+            Err(err) => {
+              if !send_error(&rt.tx, err, "WindowEventTrigger").await {
+                break;
+              }
+              continue;
+            }
+          };
+          let current_focus = Window::current();
+
+          let events = Self::diff(&windows, &current, focus, current_focus);
+          windows = current;
+          focus = current_focus;
+
+          for event in events {
+            let ctx = TriggerContext::new(event, rt.tx.clone());
+            send_err!((self.callback)(ctx).await, "WindowEventTrigger", &rt.tx, return Ok(()));
+          }
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  fn name(&self) -> String {
+    "WindowEventTrigger".to_string()
+  }
+}
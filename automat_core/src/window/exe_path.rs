@@ -59,60 +59,26 @@ pub fn get_window_exe_path(window_id: WindowIdentifier) -> Option<String> {
 ///
 /// # Safety
 ///
-/// Uses unsafe X11 API calls. Properly cleans up resources by closing the display and freeing allocated memory.
+/// Uses unsafe X11 API calls on the shared X connection. Frees memory allocated by Xlib.
 pub fn get_window_exe_path(window_id: WindowIdentifier) -> Option<String> {
+  use crate::linux::{NET_WM_PID, long_property, with_connection};
   use std::fs;
-  use std::ptr;
-  use x11::xlib::*;
-
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return None;
-    }
-
-    let focus_window = window_id.as_u64();
-    let atom_name = b"_NET_WM_PID\0".as_ptr() as *const i8;
-    let pid_atom = XInternAtom(display, atom_name, 0);
-
-    let mut actual_type: u64 = 0;
-    let mut actual_format: i32 = 0;
-    let mut nitems: u64 = 0;
-    let mut bytes_after: u64 = 0;
-    let mut prop: *mut u8 = ptr::null_mut();
-
-    let status = XGetWindowProperty(
-      display,
-      focus_window,
-      pid_atom,
-      0,
-      1,
-      0,
-      XA_CARDINAL,
-      &mut actual_type,
-      &mut actual_format,
-      &mut nitems,
-      &mut bytes_after,
-      &mut prop,
-    );
-
-    let pid = if status == 0 && !prop.is_null() && nitems > 0 {
-      let pid = *(prop as *const u32);
-      XFree(prop as *mut _);
-      pid
-    } else {
-      XCloseDisplay(display);
-      return None;
-    };
-
-    XCloseDisplay(display);
-
-    // Read the symlink from /proc/{pid}/exe
-    let exe_path = format!("/proc/{}/exe", pid);
-    fs::read_link(exe_path)
-      .ok()
-      .map(|p| p.to_string_lossy().to_string())
-  }
+  use x11::xlib::XA_CARDINAL;
+
+  let pid = with_connection(|conn| {
+    let pid_atom = conn.atom(NET_WM_PID);
+    long_property(conn.display(), window_id.as_u64(), pid_atom, XA_CARDINAL)?
+      .first()
+      .copied()
+  })
+  .ok()
+  .flatten()?;
+
+  // Read the symlink from /proc/{pid}/exe
+  let exe_path = format!("/proc/{}/exe", pid);
+  fs::read_link(exe_path)
+    .ok()
+    .map(|p| p.to_string_lossy().to_string())
 }
 
 #[cfg(target_os = "macos")]
@@ -0,0 +1,165 @@
+use crate::WindowIdentifier;
+
+/// Position and size of a window's outer frame, in screen coordinates.
+///
+/// The frame includes decorations such as the title bar and borders, so this is the area
+/// the window occupies on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WindowGeometry {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+}
+
+impl WindowGeometry {
+  pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+    Self {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  /// Returns the top-left corner.
+  pub fn position(&self) -> (i32, i32) {
+    (self.x, self.y)
+  }
+
+  /// Returns the width and height.
+  pub fn size(&self) -> (u32, u32) {
+    (self.width, self.height)
+  }
+
+  /// Returns true if the point lies inside the frame.
+  pub fn contains(&self, x: i32, y: i32) -> bool {
+    let (x, y) = (i64::from(x), i64::from(y));
+    let (left, top) = (i64::from(self.x), i64::from(self.y));
+    x >= left && y >= top && x < left + i64::from(self.width) && y < top + i64::from(self.height)
+  }
+}
+
+/// Size of the decorations around a window's client area, in pixels.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct FrameExtents {
+  pub(crate) left: u32,
+  pub(crate) right: u32,
+  pub(crate) top: u32,
+  pub(crate) bottom: u32,
+}
+
+#[cfg(target_os = "windows")]
+/// Gets the outer geometry of the window on Windows.
+///
+/// Uses the Windows API to get the window rectangle via `GetWindowRect`, which already
+/// includes the non-client area.
+/// Returns `None` if the window handle is invalid or the API call fails.
+///
+/// # Safety
+///
+/// Uses unsafe Windows API calls with raw HWND handles.
+pub fn get_window_geometry(window_id: WindowIdentifier) -> Option<WindowGeometry> {
+  use std::mem::zeroed;
+  use windows::Win32::Foundation::{HWND, RECT};
+  use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    let mut rect: RECT = zeroed();
+
+    if GetWindowRect(hwnd, &mut rect).is_ok() {
+      Some(WindowGeometry::new(
+        rect.left,
+        rect.top,
+        (rect.right - rect.left) as u32,
+        (rect.bottom - rect.top) as u32,
+      ))
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+/// Gets the outer geometry of the window on Linux.
+///
+/// X11 reports the client area only, so the frame added by the window manager is taken
+/// from `_NET_FRAME_EXTENTS` when the window manager publishes it.
+/// Returns `None` if the window ID is invalid or the X display can't be opened.
+///
+/// # Safety
+///
+/// Uses unsafe X11 API calls on the shared X connection.
+pub fn get_window_geometry(window_id: WindowIdentifier) -> Option<WindowGeometry> {
+  use crate::linux::with_connection;
+  use std::mem::zeroed;
+  use x11::xlib::{XGetWindowAttributes, XTranslateCoordinates, XWindowAttributes};
+
+  with_connection(|conn| unsafe {
+    let window = window_id.as_u64();
+    let mut attributes: XWindowAttributes = zeroed();
+    if XGetWindowAttributes(conn.display(), window, &mut attributes) == 0 {
+      return None;
+    }
+
+    let (mut x, mut y) = (0, 0);
+    let mut child = 0;
+    if XTranslateCoordinates(
+      conn.display(),
+      window,
+      conn.root(),
+      0,
+      0,
+      &mut x,
+      &mut y,
+      &mut child,
+    ) == 0
+    {
+      return None;
+    }
+
+    let extents = frame_extents(conn, window);
+    Some(WindowGeometry::new(
+      x - extents.left as i32,
+      y - extents.top as i32,
+      attributes.width as u32 + extents.left + extents.right,
+      attributes.height as u32 + extents.top + extents.bottom,
+    ))
+  })
+  .ok()
+  .flatten()
+}
+
+#[cfg(target_os = "linux")]
+/// Reads `_NET_FRAME_EXTENTS`, defaulting to no frame.
+pub(crate) fn frame_extents(
+  conn: &mut crate::linux::XConnection,
+  window: x11::xlib::Window,
+) -> FrameExtents {
+  use crate::linux::{NET_FRAME_EXTENTS, long_property};
+  use x11::xlib::XA_CARDINAL;
+
+  let atom = conn.atom(NET_FRAME_EXTENTS);
+  match long_property(conn.display(), window, atom, XA_CARDINAL).as_deref() {
+    Some([left, right, top, bottom, ..]) => FrameExtents {
+      left: *left as u32,
+      right: *right as u32,
+      top: *top as u32,
+      bottom: *bottom as u32,
+    },
+    _ => FrameExtents::default(),
+  }
+}
+
+#[cfg(target_os = "macos")]
+/// Gets the outer geometry of the window on macOS.
+///
+/// Uses Core Graphics API to get the window bounds, which include the title bar.
+/// Returns `None` if the window ID is invalid or the API call fails.
+pub fn get_window_geometry(window_id: WindowIdentifier) -> Option<WindowGeometry> {
+  let (x, y) = crate::get_window_position(window_id)?;
+  let (width, height) = crate::get_window_size(window_id)?;
+  Some(WindowGeometry::new(x, y, width, height))
+}
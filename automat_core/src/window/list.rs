@@ -1,5 +1,6 @@
-use crate::{Error, Result, Window, WindowIdentifier};
-use windows::core::BOOL;
+#[cfg(not(target_os = "linux"))]
+use crate::Error;
+use crate::{Result, Window, WindowIdentifier};
 
 #[cfg(target_os = "windows")]
 /// Lists all open windows on Windows.
@@ -16,7 +17,7 @@ pub fn list_windows() -> Result<Vec<Window>> {
 
   let mut windows = Vec::new();
 
-  unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+  unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> windows::core::BOOL {
     unsafe {
       let windows = &mut *(lparam.0 as *mut Vec<Window>);
       let window_id = WindowIdentifier::new(hwnd.0 as u64);
@@ -41,35 +42,37 @@ pub fn list_windows() -> Result<Vec<Window>> {
 #[cfg(target_os = "linux")]
 /// Lists all open windows on Linux using X11.
 ///
-/// Connects to the X11 display and queries all client windows to retrieve their window IDs and titles.
+/// Uses the `_NET_CLIENT_LIST` maintained by EWMH window managers, falling back to the
+/// children of the root window when it is not available.
 ///
 /// # Safety
 ///
 /// Uses unsafe X11 API calls for display connection and window queries.
 pub fn list_windows() -> Result<Vec<Window>> {
-  use crate::window::get_window_title;
+  use crate::linux::{NET_CLIENT_LIST, long_property, with_connection};
   use std::ptr;
-  use x11::xlib::{Display, Window, XCloseDisplay, XFree, XOpenDisplay, XQueryTree, XRootWindow};
-
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(Error::WindowListError(
-        "Failed to open X11 display".to_string(),
-      ));
+  use x11::xlib::{XA_WINDOW, XFree, XQueryTree};
+
+  with_connection(|conn| unsafe {
+    let client_list = conn.atom(NET_CLIENT_LIST);
+    if let Some(clients) = long_property(conn.display(), conn.root(), client_list, XA_WINDOW) {
+      return clients
+        .into_iter()
+        .map(|window| Window::new(WindowIdentifier::new(window)))
+        .collect();
     }
 
-    let root = XRootWindow(display, 0);
+    // Without an EWMH window manager, fall back to the children of the root window.
     let mut windows = Vec::new();
 
-    let mut root_return: Window = 0;
-    let mut parent_return: Window = 0;
-    let mut children: *mut Window = ptr::null_mut();
+    let mut root_return: x11::xlib::Window = 0;
+    let mut parent_return: x11::xlib::Window = 0;
+    let mut children: *mut x11::xlib::Window = ptr::null_mut();
     let mut nchildren: u32 = 0;
 
     if XQueryTree(
-      display,
-      root,
+      conn.display(),
+      conn.root(),
       &mut root_return,
       &mut parent_return,
       &mut children,
@@ -88,9 +91,8 @@ pub fn list_windows() -> Result<Vec<Window>> {
       }
     }
 
-    XCloseDisplay(display);
-    Ok(windows)
-  }
+    windows
+  })
 }
 
 #[cfg(target_os = "macos")]
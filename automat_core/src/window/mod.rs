@@ -1,12 +1,16 @@
 mod exe_path;
+mod geometry;
 mod list;
+mod position;
 mod size;
 mod state;
 mod titlebar;
 mod window_id;
 
 pub use exe_path::*;
+pub use geometry::*;
 pub use list::*;
+pub use position::*;
 pub use size::*;
 pub use state::*;
 pub use titlebar::*;
@@ -47,6 +51,16 @@ impl Window {
     get_window_size(self.id)
   }
 
+  /// Gets the position of this window's top-left corner in screen coordinates.
+  pub fn position(&self) -> Option<(i32, i32)> {
+    get_window_position(self.id)
+  }
+
+  /// Gets the position and size of this window's outer frame, including decorations.
+  pub fn geometry(&self) -> Option<WindowGeometry> {
+    get_window_geometry(self.id)
+  }
+
   /// Gets the state of this window.
   pub fn state(&self) -> crate::Result<WindowState> {
     get_window_state(self.id)
@@ -0,0 +1,110 @@
+use crate::WindowIdentifier;
+
+#[cfg(target_os = "windows")]
+/// Gets the position of the window on Windows.
+///
+/// Uses the Windows API to get the top-left corner via `GetWindowRect`.
+/// Returns (x, y) in screen coordinates, or `None` if the window handle is invalid or the API call fails.
+///
+/// # Safety
+///
+/// Uses unsafe Windows API calls with raw HWND handles.
+pub fn get_window_position(window_id: WindowIdentifier) -> Option<(i32, i32)> {
+  use std::mem::zeroed;
+  use windows::Win32::Foundation::{HWND, RECT};
+  use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+  unsafe {
+    let hwnd = HWND(window_id.as_u64() as *mut _);
+    let mut rect: RECT = zeroed();
+
+    if GetWindowRect(hwnd, &mut rect).is_ok() {
+      Some((rect.left, rect.top))
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+/// Gets the position of the window on Linux.
+///
+/// Uses the X11 API to translate the window origin to root window coordinates.
+/// Returns (x, y) in pixels, or `None` if the window ID is invalid or the API call fails.
+///
+/// # Safety
+///
+/// Uses unsafe X11 API calls. Requires X11 display connection.
+pub fn get_window_position(window_id: WindowIdentifier) -> Option<(i32, i32)> {
+  use crate::linux::with_connection;
+  use x11::xlib::XTranslateCoordinates;
+
+  with_connection(|conn| unsafe {
+    let (mut x, mut y) = (0, 0);
+    let mut child = 0;
+    let result = XTranslateCoordinates(
+      conn.display(),
+      window_id.as_u64(),
+      conn.root(),
+      0,
+      0,
+      &mut x,
+      &mut y,
+      &mut child,
+    );
+
+    if result != 0 { Some((x, y)) } else { None }
+  })
+  .ok()
+  .flatten()
+}
+
+#[cfg(target_os = "macos")]
+/// Gets the position of the window on macOS.
+///
+/// Uses Core Graphics API to get the window origin.
+/// Returns (x, y) in points, or `None` if the window ID is invalid or the API call fails.
+pub fn get_window_position(window_id: WindowIdentifier) -> Option<(i32, i32)> {
+  use core_foundation::array::CFArray;
+  use core_foundation::base::TCFType;
+  use core_foundation::dictionary::CFDictionary;
+  use core_foundation::number::CFNumber;
+  use core_foundation::string::CFString;
+  use core_graphics::window::{CGWindowListCopyWindowInfo, kCGWindowListOptionIncludingWindow};
+
+  unsafe {
+    let window_list = CGWindowListCopyWindowInfo(
+      kCGWindowListOptionIncludingWindow,
+      window_id.as_u64() as u32,
+    );
+
+    if window_list.is_null() {
+      return None;
+    }
+
+    let array = CFArray::<CFDictionary>::wrap_under_create_rule(window_list as *const _);
+
+    if array.len() == 0 {
+      return None;
+    }
+
+    let window_info = array.get(0);
+    let bounds_key = CFString::from_static_string("kCGWindowBounds");
+
+    let bounds_dict = window_info.find(&bounds_key)?;
+    let bounds_dict = bounds_dict as *const _ as *const CFDictionary;
+    let bounds_dict = CFDictionary::wrap_under_get_rule(bounds_dict);
+
+    let x_key = CFString::from_static_string("X");
+    let y_key = CFString::from_static_string("Y");
+
+    let x = bounds_dict
+      .find(&x_key)
+      .and_then(|x| CFNumber::wrap_under_get_rule(x as *const _).to_i64())?;
+    let y = bounds_dict
+      .find(&y_key)
+      .and_then(|y| CFNumber::wrap_under_get_rule(y as *const _).to_i64())?;
+
+    Some((x as i32, y as i32))
+  }
+}
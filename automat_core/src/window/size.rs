@@ -38,28 +38,22 @@ pub fn get_window_size(window_id: WindowIdentifier) -> Option<(u32, u32)> {
 ///
 /// Uses unsafe X11 API calls. Requires X11 display connection.
 pub fn get_window_size(window_id: WindowIdentifier) -> Option<(u32, u32)> {
+  use crate::linux::with_connection;
   use std::mem::zeroed;
-  use std::ptr;
-  use x11::xlib::{XCloseDisplay, XGetWindowAttributes, XOpenDisplay, XWindowAttributes};
+  use x11::xlib::{XGetWindowAttributes, XWindowAttributes};
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return None;
-    }
-
-    let window = window_id.as_u64();
+  with_connection(|conn| unsafe {
     let mut attributes: XWindowAttributes = zeroed();
-
-    let result = XGetWindowAttributes(display, window, &mut attributes);
-    XCloseDisplay(display);
+    let result = XGetWindowAttributes(conn.display(), window_id.as_u64(), &mut attributes);
 
     if result != 0 {
       Some((attributes.width as u32, attributes.height as u32))
     } else {
       None
     }
-  }
+  })
+  .ok()
+  .flatten()
 }
 
 #[cfg(target_os = "macos")]
@@ -119,51 +119,21 @@ pub fn get_window_state(window_id: WindowIdentifier) -> Result<WindowState> {
 /// * `minimized` corresponds to the `_NET_WM_STATE_HIDDEN` state
 /// * `enabled` checks the `InputHint` in `XWMHints`, defaulting to `true` if no hints are available
 pub fn get_window_state(window_id: WindowIdentifier) -> Result<WindowState> {
-  use std::ffi::CString;
-  use std::ptr;
+  use crate::linux::{
+    NET_WM_STATE, NET_WM_STATE_HIDDEN, NET_WM_STATE_MAXIMIZED_HORZ, NET_WM_STATE_MAXIMIZED_VERT,
+    long_property, with_connection,
+  };
+  use std::mem::zeroed;
   use x11::xlib::*;
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return Err(Error::WindowStateError(
-        "Failed to open X display".to_string(),
-      ));
-    }
-
+  with_connection(|conn| unsafe {
+    let display = conn.display();
     let window = window_id.as_u64();
 
     // Check if a window exists
-    let mut attrs = XWindowAttributes {
-      x: 0,
-      y: 0,
-      width: 0,
-      height: 0,
-      border_width: 0,
-      depth: 0,
-      visual: ptr::null_mut(),
-      root: 0,
-      class: 0,
-      bit_gravity: 0,
-      win_gravity: 0,
-      backing_store: 0,
-      backing_planes: 0,
-      backing_pixel: 0,
-      save_under: 0,
-      colormap: 0,
-      map_installed: 0,
-      map_state: 0,
-      all_event_masks: 0,
-      your_event_mask: 0,
-      do_not_propagate_mask: 0,
-      override_redirect: 0,
-      screen: ptr::null_mut(),
-    };
-
-    let status = XGetWindowAttributes(display, window, &mut attrs);
-    if status == 0 {
-      XCloseDisplay(display);
-      return Err(WindowStateError(
+    let mut attrs: XWindowAttributes = zeroed();
+    if XGetWindowAttributes(display, window, &mut attrs) == 0 {
+      return Err(Error::WindowStateError(
         "Failed to get window attributes".to_string(),
       ));
     }
@@ -171,67 +141,16 @@ pub fn get_window_state(window_id: WindowIdentifier) -> Result<WindowState> {
     let visible = attrs.map_state == IsViewable;
 
     // Check for _NET_WM_STATE to determine maximized/minimized
-    let net_wm_state = CString::new("_NET_WM_STATE").unwrap();
-    let atom_net_wm_state = XInternAtom(display, net_wm_state.as_ptr(), 0);
+    let atom_net_wm_state = conn.atom(NET_WM_STATE);
+    let maximized_horz = conn.atom(NET_WM_STATE_MAXIMIZED_HORZ);
+    let maximized_vert = conn.atom(NET_WM_STATE_MAXIMIZED_VERT);
+    let hidden = conn.atom(NET_WM_STATE_HIDDEN);
 
-    let mut actual_type: u64 = 0;
-    let mut actual_format: i32 = 0;
-    let mut nitems: u64 = 0;
-    let mut bytes_after: u64 = 0;
-    let mut prop: *mut u8 = ptr::null_mut();
-
-    XGetWindowProperty(
-      display,
-      window,
-      atom_net_wm_state,
-      0,
-      1024,
-      0,
-      AnyPropertyType as u64,
-      &mut actual_type,
-      &mut actual_format,
-      &mut nitems,
-      &mut bytes_after,
-      &mut prop,
-    );
-
-    let mut maximized = false;
-    let mut minimized = false;
-
-    if !prop.is_null() {
-      let states = std::slice::from_raw_parts(prop as *const u64, nitems as usize);
-
-      let maximized_horz = XInternAtom(
-        display,
-        CString::new("_NET_WM_STATE_MAXIMIZED_HORZ")
-          .unwrap()
-          .as_ptr(),
-        0,
-      );
-      let maximized_vert = XInternAtom(
-        display,
-        CString::new("_NET_WM_STATE_MAXIMIZED_VERT")
-          .unwrap()
-          .as_ptr(),
-        0,
-      );
-      let hidden = XInternAtom(
-        display,
-        CString::new("_NET_WM_STATE_HIDDEN").unwrap().as_ptr(),
-        0,
-      );
-
-      for &state in states {
-        if state == maximized_horz || state == maximized_vert {
-          maximized = true;
-        }
-        if state == hidden {
-          minimized = true;
-        }
-      }
-
-      XFree(prop as *mut _);
-    }
+    let states = long_property(display, window, atom_net_wm_state, XA_ATOM).unwrap_or_default();
+    let maximized = states
+      .iter()
+      .any(|&state| state == maximized_horz || state == maximized_vert);
+    let minimized = states.contains(&hidden);
 
     // X11 doesn't have a direct "enabled" concept
     // We check if the window accepts input
@@ -244,15 +163,13 @@ pub fn get_window_state(window_id: WindowIdentifier) -> Result<WindowState> {
       true // Default to enabled if no hints
     };
 
-    XCloseDisplay(display);
-
     Ok(WindowState {
       visible,
       maximized,
       minimized,
       enabled,
     })
-  }
+  })?
 }
 
 #[cfg(target_os = "macos")]
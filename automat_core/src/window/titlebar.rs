@@ -35,34 +35,27 @@ pub fn get_window_title(window_id: WindowIdentifier) -> Option<String> {
 ///
 /// # Safety
 ///
-/// Uses unsafe X11 API calls. Properly cleans up resources by closing the display and freeing allocated memory.
+/// Uses unsafe X11 API calls on the shared X connection. Frees memory allocated by Xlib.
 pub fn get_window_title(window_id: WindowIdentifier) -> Option<String> {
+  use crate::linux::with_connection;
   use std::ffi::CStr;
   use std::ptr;
   use x11::xlib::*;
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return None;
-    }
-
-    let focus_window = window_id.as_u64();
+  with_connection(|conn| unsafe {
     let mut name: *mut i8 = ptr::null_mut();
-    let status = XFetchName(display, focus_window, &mut name);
+    let status = XFetchName(conn.display(), window_id.as_u64(), &mut name);
 
-    let title = if status != 0 && !name.is_null() {
-      let c_str = CStr::from_ptr(name);
-      let title = c_str.to_string_lossy().to_string();
+    if status != 0 && !name.is_null() {
+      let title = CStr::from_ptr(name).to_string_lossy().to_string();
       XFree(name as *mut _);
       Some(title)
     } else {
       None
-    };
-
-    XCloseDisplay(display);
-    title
-  }
+    }
+  })
+  .ok()
+  .flatten()
 }
 
 #[cfg(target_os = "macos")]
@@ -32,29 +32,32 @@ pub fn get_current_window_identifier() -> Option<WindowIdentifier> {
 
 #[cfg(target_os = "linux")]
 /// Gets the identifier of the currently focused window on Linux.
+///
+/// Prefers `_NET_ACTIVE_WINDOW` from the window manager, which names the top-level client
+/// window, and falls back to the X input focus.
 /// Returns the X11 Window ID, or `None` if the X display can't be opened or no window has focus.
 pub fn get_current_window_identifier() -> Option<WindowIdentifier> {
-  use std::ptr;
-  use x11::xlib::*;
+  use crate::linux::{NET_ACTIVE_WINDOW, window_property, with_connection};
+  use x11::xlib::{Window, XGetInputFocus};
 
-  unsafe {
-    let display = XOpenDisplay(ptr::null());
-    if display.is_null() {
-      return None;
+  with_connection(|conn| {
+    let active_window = conn.atom(NET_ACTIVE_WINDOW);
+    if let Some(window) = window_property(conn.display(), conn.root(), active_window)
+      && window != 0
+    {
+      return Some(WindowIdentifier::new(window));
     }
 
+    // Window managers without EWMH support only track the input focus.
     let mut focus_window: Window = 0;
     let mut revert_to: i32 = 0;
-    XGetInputFocus(display, &mut focus_window, &mut revert_to);
-
-    XCloseDisplay(display);
+    unsafe { XGetInputFocus(conn.display(), &mut focus_window, &mut revert_to) };
 
-    if focus_window == 0 {
-      None
-    } else {
-      Some(WindowIdentifier::new(focus_window))
-    }
-  }
+    // Values up to 1 are `None` and `PointerRoot`, not windows.
+    (focus_window > 1).then(|| WindowIdentifier::new(focus_window))
+  })
+  .ok()
+  .flatten()
 }
 
 #[cfg(target_os = "macos")]